@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use iaaf_scoring::error::IaafScoringError;
+use iaaf_scoring::event::OutdoorEvent;
+use iaaf_scoring::gender::Gender;
+use iaaf_scoring::methods::sources::{CsvDirectory, EmbeddedArchives, TableSource};
+use iaaf_scoring::methods::{Edition, EmbeddedTables};
+use iaaf_scoring::preformance::Preformance;
+
+/// The tables the benchmarks score with: a short sprint table and two long ones
+const EVENTS: [(OutdoorEvent, &str, f64, f64, usize); 3] = [
+    (OutdoorEvent::Track100m, "100m", 9.46, 0.01, 1_500),
+    (OutdoorEvent::JavelinThrow, "JT", 103.0, -0.01, 9_000),
+    (OutdoorEvent::Marathon, "Marathon", 7_020.0, 1.0, 12_000),
+];
+
+/// Makes sure the tables are loaded. When the embedded archives don't have them (the CSV files
+/// weren't generated), the tables of `generate_tables` are loaded instead
+fn load_tables() {
+    let mut method = Edition::Iaaf2017.outdoor();
+    let missing = EVENTS.iter().any(|&(event, ..)| {
+        matches!(
+            method.performance_for_points(event, Gender::Male, 1000),
+            Err(IaafScoringError::TableMissing(_))
+        )
+    });
+    if !missing {
+        return;
+    }
+
+    let dir = generate_tables();
+    EmbeddedTables::load_source(&CsvDirectory::new(&dir)).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Writes tables of about the same size as the real ones to a temporary directory, and returns it
+fn generate_tables() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("iaaf_scoring_bench_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for (_, codename, best, step, rows) in EVENTS {
+        let mut csv = String::from("performance,points\n");
+        for row in 0..rows {
+            let points = 1400 - row * 1400 / rows;
+            csv.push_str(&format!("{:.2},{}\n", best + row as f64 * step, points));
+        }
+        fs::write(table_path(&dir, codename), csv).unwrap();
+    }
+
+    dir
+}
+
+fn table_path(dir: &std::path::Path, codename: &str) -> PathBuf {
+    dir.join(format!("Table Outdoor 2017 - MALE - {}.csv", codename))
+}
+
+fn time(seconds: f64) -> Preformance {
+    Preformance::Time(Duration::from_secs_f64(seconds))
+}
+
+fn cold_init(c: &mut Criterion) {
+    // without tables in the embedded archives there is nothing to decompress, so the generated
+    // tables are parsed instead
+    let archived = EmbeddedTables::default().load(&EmbeddedArchives).unwrap_or(0) > 0;
+    let dir = (!archived).then(generate_tables);
+    let (name, source): (_, Box<dyn TableSource>) = match &dir {
+        None => ("cold init (embedded archives)", Box::new(EmbeddedArchives)),
+        Some(dir) => ("cold init (generated tables)", Box::new(CsvDirectory::new(dir))),
+    };
+
+    // every iteration reads into a fresh instance, like the first lookups do
+    c.bench_function(name, |b| {
+        b.iter_batched(
+            EmbeddedTables::default,
+            |mut tables| tables.load(black_box(source.as_ref())).map(|_| tables),
+            BatchSize::SmallInput,
+        )
+    });
+
+    if let Some(dir) = dir {
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+fn single_score(c: &mut Criterion) {
+    load_tables();
+    let mut method = Edition::Iaaf2017.outdoor();
+
+    c.bench_function("score 100m", |b| {
+        b.iter(|| method.score(OutdoorEvent::Track100m, Gender::Male, black_box(time(10.555))))
+    });
+    c.bench_function("score javelin throw", |b| {
+        b.iter(|| method.score(OutdoorEvent::JavelinThrow, Gender::Male, black_box(Preformance::Distance(61.234))))
+    });
+    c.bench_function("score marathon", |b| {
+        b.iter(|| method.score(OutdoorEvent::Marathon, Gender::Male, black_box(time(9_123.5))))
+    });
+}
+
+fn reverse_lookup(c: &mut Criterion) {
+    load_tables();
+    let mut method = Edition::Iaaf2017.outdoor();
+
+    c.bench_function("performance for points 100m", |b| {
+        b.iter(|| method.performance_for_points(OutdoorEvent::Track100m, Gender::Male, black_box(1000)))
+    });
+    c.bench_function("performance for points marathon", |b| {
+        b.iter(|| method.performance_for_points(OutdoorEvent::Marathon, Gender::Male, black_box(1000)))
+    });
+}
+
+fn batch_score(c: &mut Criterion) {
+    load_tables();
+    let mut method = Edition::Iaaf2017.outdoor();
+    let results = (0..10_000)
+        .map(|i| match i % 3 {
+            0 => (OutdoorEvent::Track100m, Gender::Male, time(10.0 + (i % 200) as f64 / 100.0)),
+            1 => (OutdoorEvent::JavelinThrow, Gender::Male, Preformance::Distance(50.0 + (i % 400) as f64 / 10.0)),
+            _ => (OutdoorEvent::Marathon, Gender::Male, time(7_500.0 + (i % 3_000) as f64)),
+        })
+        .collect::<Vec<_>>();
+
+    c.bench_function("score batch of 10000", |b| {
+        b.iter(|| method.score_batch(black_box(&results)))
+    });
+    c.bench_function("score 10000 one by one", |b| {
+        b.iter(|| {
+            results
+                .iter()
+                .map(|&(event, gender, performance)| method.score(event, gender, performance))
+                .collect::<Vec<_>>()
+        })
+    });
+}
+
+criterion_group!(benches, cold_init, single_score, reverse_lookup, batch_score);
+criterion_main!(benches);
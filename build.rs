@@ -0,0 +1,13 @@
+/// With the `static-tables` feature, compiles every table of the embedded archives into
+/// `static_tables.rs`
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=resources");
+
+    #[cfg(feature = "static-tables")]
+    {
+        let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+        let out = std::path::Path::new(&out_dir).join("static_tables.rs");
+        iaaf_scoring_static_tables::write_static_tables(std::path::Path::new("resources"), &out);
+    }
+}
@@ -1,2 +1,4 @@
 XZ_OPT=-9 tar --lzma -cvf wa2022.tar.lzma wa_2022_tables_indoor/*.csv wa_2022_tables_outdoor/*.csv
 XZ_OPT=-9 tar --lzma -cvf iaaf2017.tar.lzma iaaf_2017_tables_indoor/*.csv iaaf_2017_tables_outdoor/*.csv
+# WMA age factors, one file per gender: "WMA 2023 - MALE.csv" and "WMA 2023 - FEMALE.csv"
+XZ_OPT=-9 tar --lzma -cvf wma2023.tar.lzma wma_age_factors/*.csv
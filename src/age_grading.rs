@@ -0,0 +1,274 @@
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "runtime-tables")]
+use std::io::Cursor;
+#[cfg(feature = "runtime-tables")]
+use rust_embed::RustEmbed;
+use crate::error::IaafScoringError;
+use crate::event::{MeasurementKind, OutdoorEvent};
+use crate::gender::Gender;
+#[cfg(feature = "runtime-tables")]
+use crate::methods::sources::unpack_lzma_archive;
+use crate::methods::{Score, ScoringMethod};
+use crate::preformance::Preformance;
+use crate::Codename;
+#[cfg(feature = "runtime-tables")]
+use lazy_static::lazy_static;
+#[cfg(feature = "runtime-tables")]
+use log::{error, trace};
+
+#[cfg(feature = "runtime-tables")]
+#[derive(RustEmbed)]
+#[folder = "resources/"]
+#[include = "wma*.tar.lzma"]
+struct EmbeddedLZMAAgeFactors;
+
+/// The archive `resources/compress_tables.sh` packs the age factors into
+#[cfg(feature = "runtime-tables")]
+const EMBEDDED_ARCHIVE: &str = "wma2023.tar.lzma";
+
+#[cfg(feature = "runtime-tables")]
+lazy_static! {
+    static ref AGE_FACTORS: Result<AgeGrading, IaafScoringError> = AgeGrading::init();
+}
+
+/// The WMA age factors of an event for one gender
+#[derive(Clone, PartialEq, Debug)]
+pub struct AgeFactorTable {
+    /// The open class standard, the best performance at any age (seconds or meters)
+    pub open_standard: f64,
+    /// The age factor per age, 1.0 for ages where athletes are at their best
+    pub factors: BTreeMap<u8, f64>,
+}
+
+/// A performance converted to the equivalent performance of an athlete in their prime
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AgeGraded {
+    pub age: u8,
+    pub factor: f64,
+    pub performance: Preformance,
+    /// The performance multiplied (times) or divided (distances) by the age factor
+    pub graded: Preformance,
+    /// The graded performance as a percentage of the open class standard
+    pub percentage: f64,
+}
+
+/// Age grades performances with the WMA age factor tables
+#[derive(Clone, Default, Debug)]
+pub struct AgeGrading {
+    tables: HashMap<(OutdoorEvent, Gender), AgeFactorTable>,
+}
+
+impl AgeGrading {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the embedded WMA age factor tables, which are parsed the first time they're used.
+    /// Fails with `TableMissing` if no age factors were embedded (see `resources/compress_tables.sh`)
+    #[cfg(feature = "runtime-tables")]
+    pub fn embedded() -> Result<&'static Self, IaafScoringError> {
+        AGE_FACTORS.as_ref().map_err(Clone::clone)
+    }
+
+    #[cfg(feature = "runtime-tables")]
+    fn init() -> Result<Self, IaafScoringError> {
+        // without the archive every event would be missing, say so once instead
+        if EmbeddedLZMAAgeFactors::iter().next().is_none() {
+            return Err(IaafScoringError::TableMissing(EMBEDDED_ARCHIVE.to_string()));
+        }
+
+        let mut grading = Self::new();
+        trace!("Listing embedded LZMA age factors");
+        for i in EmbeddedLZMAAgeFactors::iter() {
+            trace!("- {}", i);
+
+            let file = EmbeddedLZMAAgeFactors::get(&i)
+                .ok_or_else(|| IaafScoringError::CorruptArchive(format!("{} is not embedded", i)))?;
+            for (path, buf) in unpack_lzma_archive(&i, &file.data)? {
+                let gender = if path.contains("FEMALE") {
+                    Gender::Female
+                } else if path.contains("MALE") {
+                    Gender::Male
+                } else {
+                    error!("Age factors for unknown gender: {}", path);
+                    continue;
+                };
+
+                for (event, table) in parse_age_factors(&path, buf)? {
+                    grading.insert(event, gender, table);
+                }
+            }
+        }
+
+        Ok(grading)
+    }
+
+    pub fn insert(&mut self, event: OutdoorEvent, gender: Gender, table: AgeFactorTable) {
+        self.tables.insert((event, gender), table);
+    }
+
+    pub fn table(&self, event: OutdoorEvent, gender: Gender) -> Option<&AgeFactorTable> {
+        self.tables.get(&(event, gender))
+    }
+
+    /// Returns the age factor for the athlete, fails if there are no factors for the event or the
+    /// age isn't in the table
+    pub fn factor(&self, event: OutdoorEvent, gender: Gender, age: u8) -> Result<f64, IaafScoringError> {
+        let table = self.table(event, gender).ok_or_else(|| {
+            IaafScoringError::TableMissing(format!("Age Factors {} - {}", gender.to_codename(), event.to_codename()))
+        })?;
+
+        table.factors.get(&age).copied().ok_or_else(|| IaafScoringError::AgeOutOfRange {
+            event: event.to_codename().to_string(),
+            age,
+        })
+    }
+
+    /// Age grades the performance of an athlete of the given age. Hand times are converted to fully
+    /// automatic times first, DNF, NM, etc. are graded to themselves with a percentage of 0
+    pub fn grade(
+        &self,
+        event: OutdoorEvent,
+        gender: Gender,
+        age: u8,
+        performance: Preformance,
+    ) -> Result<AgeGraded, IaafScoringError> {
+        let factor = self.factor(event, gender, age)?;
+        let open_standard = self.table(event, gender).map_or(0.0, |t| t.open_standard);
+        let fully_automatic = event.to_fully_automatic(performance);
+
+        let (graded, percentage) = match (event.measurement_kind(), fully_automatic.to_number()) {
+            (_, None) => (performance, 0.0),
+            (MeasurementKind::Time, Some(time)) => {
+                let graded = time * factor;
+                (event.preformance(graded), open_standard / graded * 100.0)
+            }
+            (MeasurementKind::Distance | MeasurementKind::Points, Some(mark)) => {
+                let graded = mark / factor;
+                (event.preformance(graded), graded / open_standard * 100.0)
+            }
+        };
+
+        Ok(AgeGraded {
+            age,
+            factor,
+            performance,
+            graded,
+            percentage,
+        })
+    }
+
+    /// Age grades the performance, then scores the graded performance with the scoring method
+    pub fn score<M: ScoringMethod<OutdoorEvent> + ?Sized>(
+        &self,
+        method: &mut M,
+        event: OutdoorEvent,
+        gender: Gender,
+        age: u8,
+        performance: Preformance,
+    ) -> Result<(AgeGraded, Score<OutdoorEvent>), IaafScoringError> {
+        let graded = self.grade(event, gender, age, performance)?;
+        let score = method.score(event, gender, graded.graded)?;
+
+        Ok((graded, score))
+    }
+}
+
+/// Parses the age factors of one gender, in the layout of the WMA spreadsheets: a row per event
+/// with its codename, the open class standard and the factor for every age in the header, e.g.
+/// `event,OC,35,36,...,100`. Rows of unknown events are skipped
+#[cfg(feature = "runtime-tables")]
+fn parse_age_factors(name: &str, data: Vec<u8>) -> Result<Vec<(OutdoorEvent, AgeFactorTable)>, IaafScoringError> {
+    let malformed = |line: Option<u64>, reason: String| IaafScoringError::MalformedRow {
+        table: name.to_string(),
+        line: line.unwrap_or_default(),
+        reason,
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b',')
+        .has_headers(true)
+        .from_reader(Cursor::new(data));
+    let ages = reader
+        .headers()
+        .map_err(|e| malformed(Some(1), e.to_string()))?
+        .iter()
+        .skip(2)
+        .map(|age| age.trim().parse::<u8>().map_err(|e| malformed(Some(1), format!("age {}: {}", age, e))))
+        .collect::<Result<Vec<u8>, IaafScoringError>>()?;
+
+    let mut tables = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| malformed(e.position().map(|p| p.line()), e.to_string()))?;
+        let line = record.position().map(|p| p.line());
+        let number = |field: &str| field.trim().parse::<f64>().map_err(|e| malformed(line, format!("{}: {}", field, e)));
+
+        let codename = record.get(0).unwrap_or_default().trim();
+        let event = match OutdoorEvent::from_codename(codename) {
+            Some(event) => event,
+            None => {
+                error!("Age factors for invalid outdoor event {}", codename);
+                continue;
+            }
+        };
+        let open_standard = number(record.get(1).ok_or_else(|| malformed(line, "missing column 1".to_string()))?)?;
+
+        let mut factors = BTreeMap::new();
+        for (age, factor) in ages.iter().zip(record.iter().skip(2)) {
+            // the spreadsheets leave factors empty for ages nobody competes at
+            if !factor.trim().is_empty() {
+                factors.insert(*age, number(factor)?);
+            }
+        }
+
+        tables.push((event, AgeFactorTable { open_standard, factors }));
+    }
+
+    Ok(tables)
+}
+
+#[cfg(feature = "runtime-tables")]
+#[test]
+fn test_age_grading() {
+    let data = b"event,OC,50,51,52\n100m,9.58,0.8868,0.8809,\nLJ,8.95,0.7905,0.7814,0.7722\nXYZ,1,1,1,1\n".to_vec();
+    let mut grading = AgeGrading::new();
+    for (event, table) in parse_age_factors("test.csv", data).unwrap() {
+        grading.insert(event, Gender::Male, table);
+    }
+    assert!(grading.table(OutdoorEvent::Track100m, Gender::Male).is_some());
+
+    let graded = grading
+        .grade(OutdoorEvent::Track100m, Gender::Male, 50, Preformance::Time(std::time::Duration::from_millis(12000)))
+        .unwrap();
+    assert!((graded.graded.to_number().unwrap() - 10.6416).abs() < 1e-6);
+    assert!((graded.percentage - 90.024).abs() < 1e-3);
+
+    let graded = grading.grade(OutdoorEvent::LongJump, Gender::Male, 52, Preformance::Distance(5.5)).unwrap();
+    assert!((graded.graded.to_number().unwrap() - 7.1225).abs() < 1e-4);
+    assert!((graded.percentage - 79.58).abs() < 1e-2);
+
+    assert_eq!(
+        grading.factor(OutdoorEvent::Track100m, Gender::Male, 52),
+        Err(IaafScoringError::AgeOutOfRange { event: "100m".to_string(), age: 52 })
+    );
+    assert!(matches!(
+        grading.factor(OutdoorEvent::Track100m, Gender::Female, 50),
+        Err(IaafScoringError::TableMissing(_))
+    ));
+}
+
+#[cfg(feature = "runtime-tables")]
+#[test]
+fn test_embedded_age_factors() {
+    match AgeGrading::embedded() {
+        // the open class standard is graded to itself at the ages without a factor
+        Ok(grading) => {
+            let table = grading.table(OutdoorEvent::Track100m, Gender::Male).expect("no 100m age factors");
+            let (&age, _) = table.factors.iter().find(|(_, factor)| **factor == 1.0).expect("no factor of 1.0");
+            let open_standard = Preformance::Time(std::time::Duration::from_secs_f64(table.open_standard));
+            let graded = grading.grade(OutdoorEvent::Track100m, Gender::Male, age, open_standard).unwrap();
+            assert!((graded.percentage - 100.0).abs() < 1e-6);
+        }
+        Err(e) => assert_eq!(e, IaafScoringError::TableMissing(EMBEDDED_ARCHIVE.to_string())),
+    }
+}
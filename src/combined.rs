@@ -0,0 +1,228 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use crate::error::IaafScoringError;
+use crate::event::{IndoorEvent, MeasurementKind, OutdoorEvent};
+use crate::gender::Gender;
+use crate::preformance::Preformance;
+use crate::Codename;
+
+/// How the performance of a discipline is put into the combined events formula
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum DisciplineKind {
+    /// Points = A·(B−P)^C, with P the time in seconds
+    Track,
+    /// Points = A·(P−B)^C, with P the height or distance in centimeters
+    Jump,
+    /// Points = A·(P−B)^C, with P the distance in meters
+    Throw,
+}
+
+/// A single discipline of a combined event, with the coefficients of its scoring formula
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Discipline {
+    pub name: &'static str,
+    pub kind: DisciplineKind,
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+impl DisciplineKind {
+    pub fn measurement_kind(&self) -> MeasurementKind {
+        match self {
+            DisciplineKind::Track => MeasurementKind::Time,
+            DisciplineKind::Jump | DisciplineKind::Throw => MeasurementKind::Distance,
+        }
+    }
+}
+
+impl Discipline {
+    const fn new(name: &'static str, kind: DisciplineKind, a: f64, b: f64, c: f64) -> Self {
+        Self { name, kind, a, b, c }
+    }
+
+    /// Returns the points for the performance (0 for DNF, NM, etc.), or an error if the performance
+    /// is of the wrong kind for this discipline
+    pub fn points(&self, performance: Preformance) -> Result<u64, IaafScoringError> {
+        // hand times are converted like they are in the individual events, indoor ones included
+        let performance = match self.kind {
+            DisciplineKind::Track => match OutdoorEvent::from_codename(self.name) {
+                Some(event) => event.to_fully_automatic(performance),
+                None => IndoorEvent::from_codename(self.name)
+                    .map_or(performance, |event| event.to_fully_automatic(performance)),
+            },
+            DisciplineKind::Jump | DisciplineKind::Throw => performance,
+        };
+
+        let p = match (self.kind, performance) {
+            (_, Preformance::NoResult(_)) => return Ok(0),
+            (DisciplineKind::Track, Preformance::Time(duration) | Preformance::HandTime(duration)) => {
+                duration.as_secs_f64()
+            }
+            // marks are measured in whole centimeters, round to get rid of floating point errors
+            (DisciplineKind::Jump, Preformance::Distance(meters)) => (meters * 100.0).round(),
+            (DisciplineKind::Throw, Preformance::Distance(meters)) => meters,
+            _ => return Err(IaafScoringError::WrongPreformanceKind {
+                event: self.name.to_string(),
+                expected: self.kind.measurement_kind(),
+                preformance: performance,
+            }),
+        };
+
+        let difference = match self.kind {
+            DisciplineKind::Track => self.b - p,
+            DisciplineKind::Jump | DisciplineKind::Throw => p - self.b,
+        };
+
+        if difference <= 0.0 {
+            return Ok(0);
+        }
+
+        Ok((self.a * difference.powf(self.c)).floor() as u64)
+    }
+}
+
+// Men's disciplines
+const MEN_60M: Discipline = Discipline::new("60m", DisciplineKind::Track, 58.015, 11.5, 1.81);
+const MEN_100M: Discipline = Discipline::new("100m", DisciplineKind::Track, 25.4347, 18.0, 1.81);
+const MEN_200M: Discipline = Discipline::new("200m", DisciplineKind::Track, 5.8425, 38.0, 1.81);
+const MEN_400M: Discipline = Discipline::new("400m", DisciplineKind::Track, 1.53775, 82.0, 1.81);
+const MEN_1000M: Discipline = Discipline::new("1000m", DisciplineKind::Track, 0.08713, 305.5, 1.85);
+const MEN_1500M: Discipline = Discipline::new("1500m", DisciplineKind::Track, 0.03768, 480.0, 1.85);
+const MEN_60M_HURDLES: Discipline = Discipline::new("60mH", DisciplineKind::Track, 20.5173, 15.5, 1.92);
+const MEN_110M_HURDLES: Discipline = Discipline::new("110mH", DisciplineKind::Track, 5.74352, 28.5, 1.92);
+const MEN_HIGH_JUMP: Discipline = Discipline::new("HJ", DisciplineKind::Jump, 0.8465, 75.0, 1.42);
+const MEN_POLE_VAULT: Discipline = Discipline::new("PV", DisciplineKind::Jump, 0.2797, 100.0, 1.35);
+const MEN_LONG_JUMP: Discipline = Discipline::new("LJ", DisciplineKind::Jump, 0.14354, 220.0, 1.4);
+const MEN_SHOT_PUT: Discipline = Discipline::new("SP", DisciplineKind::Throw, 51.39, 1.5, 1.05);
+const MEN_DISCUS_THROW: Discipline = Discipline::new("DT", DisciplineKind::Throw, 12.91, 4.0, 1.1);
+const MEN_JAVELIN_THROW: Discipline = Discipline::new("JT", DisciplineKind::Throw, 10.14, 7.0, 1.08);
+
+// Women's disciplines
+const WOMEN_100M: Discipline = Discipline::new("100m", DisciplineKind::Track, 17.857, 21.0, 1.81);
+const WOMEN_200M: Discipline = Discipline::new("200m", DisciplineKind::Track, 4.99087, 42.5, 1.81);
+const WOMEN_400M: Discipline = Discipline::new("400m", DisciplineKind::Track, 1.34285, 91.7, 1.81);
+const WOMEN_800M: Discipline = Discipline::new("800m", DisciplineKind::Track, 0.11193, 254.0, 1.88);
+const WOMEN_1500M: Discipline = Discipline::new("1500m", DisciplineKind::Track, 0.02883, 535.0, 1.88);
+const WOMEN_60M_HURDLES: Discipline = Discipline::new("60mH", DisciplineKind::Track, 20.0479, 17.0, 1.835);
+const WOMEN_100M_HURDLES: Discipline = Discipline::new("100mH", DisciplineKind::Track, 9.23076, 26.7, 1.835);
+const WOMEN_HIGH_JUMP: Discipline = Discipline::new("HJ", DisciplineKind::Jump, 1.84523, 75.0, 1.348);
+const WOMEN_POLE_VAULT: Discipline = Discipline::new("PV", DisciplineKind::Jump, 0.44125, 100.0, 1.35);
+const WOMEN_LONG_JUMP: Discipline = Discipline::new("LJ", DisciplineKind::Jump, 0.188807, 210.0, 1.41);
+const WOMEN_SHOT_PUT: Discipline = Discipline::new("SP", DisciplineKind::Throw, 56.0211, 1.5, 1.05);
+const WOMEN_DISCUS_THROW: Discipline = Discipline::new("DT", DisciplineKind::Throw, 12.3311, 3.0, 1.1);
+const WOMEN_JAVELIN_THROW: Discipline = Discipline::new("JT", DisciplineKind::Throw, 15.9803, 3.8, 1.04);
+
+const DECATHLON: [Discipline; 10] = [
+    MEN_100M, MEN_LONG_JUMP, MEN_SHOT_PUT, MEN_HIGH_JUMP, MEN_400M,
+    MEN_110M_HURDLES, MEN_DISCUS_THROW, MEN_POLE_VAULT, MEN_JAVELIN_THROW, MEN_1500M,
+];
+const WOMEN_DECATHLON: [Discipline; 10] = [
+    WOMEN_100M, WOMEN_DISCUS_THROW, WOMEN_POLE_VAULT, WOMEN_JAVELIN_THROW, WOMEN_400M,
+    WOMEN_100M_HURDLES, WOMEN_LONG_JUMP, WOMEN_SHOT_PUT, WOMEN_HIGH_JUMP, WOMEN_1500M,
+];
+const HEPTATHLON: [Discipline; 7] = [
+    WOMEN_100M_HURDLES, WOMEN_HIGH_JUMP, WOMEN_SHOT_PUT, WOMEN_200M,
+    WOMEN_LONG_JUMP, WOMEN_JAVELIN_THROW, WOMEN_800M,
+];
+const INDOOR_HEPTATHLON: [Discipline; 7] = [
+    MEN_60M, MEN_LONG_JUMP, MEN_SHOT_PUT, MEN_HIGH_JUMP,
+    MEN_60M_HURDLES, MEN_POLE_VAULT, MEN_1000M,
+];
+const INDOOR_PENTATHLON: [Discipline; 5] = [
+    WOMEN_60M_HURDLES, WOMEN_HIGH_JUMP, WOMEN_SHOT_PUT, WOMEN_LONG_JUMP, WOMEN_800M,
+];
+const YOUTH_OCTATHLON: [Discipline; 8] = [
+    MEN_100M, MEN_LONG_JUMP, MEN_SHOT_PUT, MEN_400M,
+    MEN_110M_HURDLES, MEN_HIGH_JUMP, MEN_JAVELIN_THROW, MEN_1000M,
+];
+const MASTERS_PENTATHLON_MEN: [Discipline; 5] = [
+    MEN_LONG_JUMP, MEN_JAVELIN_THROW, MEN_200M, MEN_DISCUS_THROW, MEN_1500M,
+];
+const MASTERS_PENTATHLON_WOMEN: [Discipline; 5] = [
+    WOMEN_100M_HURDLES, WOMEN_HIGH_JUMP, WOMEN_SHOT_PUT, WOMEN_LONG_JUMP, WOMEN_800M,
+];
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum CombinedEvent {
+    /// Men's decathlon, also used for the U20 decathlon (with junior implements)
+    Decathlon,
+    /// Women's decathlon
+    WomenDecathlon,
+    /// Women's heptathlon, also used for the U18 and U20 heptathlon (with youth implements)
+    Heptathlon,
+    /// Men's indoor heptathlon
+    IndoorHeptathlon,
+    /// Women's indoor pentathlon
+    IndoorPentathlon,
+    /// U18 boys' octathlon
+    YouthOctathlon,
+    /// WMA outdoor pentathlon, the performances should already be age graded
+    MastersPentathlon(Gender),
+}
+
+impl CombinedEvent {
+    /// Returns the disciplines of the event, in the order they are contested
+    pub fn disciplines(&self) -> &'static [Discipline] {
+        match self {
+            CombinedEvent::Decathlon => &DECATHLON,
+            CombinedEvent::WomenDecathlon => &WOMEN_DECATHLON,
+            CombinedEvent::Heptathlon => &HEPTATHLON,
+            CombinedEvent::IndoorHeptathlon => &INDOOR_HEPTATHLON,
+            CombinedEvent::IndoorPentathlon => &INDOOR_PENTATHLON,
+            CombinedEvent::YouthOctathlon => &YOUTH_OCTATHLON,
+            CombinedEvent::MastersPentathlon(Gender::Male) => &MASTERS_PENTATHLON_MEN,
+            CombinedEvent::MastersPentathlon(Gender::Female) => &MASTERS_PENTATHLON_WOMEN,
+        }
+    }
+
+    /// Scores the performances, which must be given in the order of `disciplines()`.
+    ///
+    /// Fails if the amount of performances doesn't match the amount of disciplines, or if a
+    /// performance is of the wrong kind for its discipline
+    pub fn score(&self, performances: &[Preformance]) -> Result<CombinedScore, IaafScoringError> {
+        let disciplines = self.disciplines();
+        if performances.len() != disciplines.len() {
+            return Err(IaafScoringError::PreformanceCountMismatch {
+                expected: disciplines.len(),
+                found: performances.len(),
+            });
+        }
+
+        let mut scores = Vec::with_capacity(disciplines.len());
+        let mut total = 0;
+        for (discipline, performance) in disciplines.iter().zip(performances) {
+            let points = discipline.points(*performance)?;
+            total += points;
+
+            scores.push(DisciplineScore {
+                discipline: *discipline,
+                performance: *performance,
+                points,
+                cumulative: total,
+            });
+        }
+
+        Ok(CombinedScore {
+            disciplines: scores,
+            total,
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DisciplineScore {
+    pub discipline: Discipline,
+    pub performance: Preformance,
+    pub points: u64,
+    /// The total amount of points after this discipline
+    pub cumulative: u64,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct CombinedScore {
+    pub disciplines: Vec<DisciplineScore>,
+    pub total: u64,
+}
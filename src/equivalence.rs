@@ -0,0 +1,93 @@
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use crate::error::IaafScoringError;
+use crate::event::OutdoorEvent;
+use crate::gender::Gender;
+use crate::methods::ScoringMethod;
+use crate::preformance::Preformance;
+use crate::Codename;
+use strum::IntoEnumIterator;
+
+/// A performance that earns the same amount of points as the performance it was converted from
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Equivalent {
+    pub event: OutdoorEvent,
+    pub gender: Gender,
+    pub performance: Preformance,
+    pub points: u64,
+}
+
+impl Display for Equivalent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:<12} {:<8} {:>14} {:>6}",
+            self.event.to_codename(),
+            self.gender.to_codename(),
+            self.event.format_preformance(self.performance),
+            self.points
+        )
+    }
+}
+
+/// Converts a performance in one event to the performance in another event (and possibly gender)
+/// that earns the same amount of points.
+///
+/// Returns `None` if the performance falls off the table, and thus has no meaningful equivalent
+pub fn equivalent_performance<M: ScoringMethod<OutdoorEvent> + ?Sized>(
+    method: &mut M,
+    event: OutdoorEvent,
+    gender: Gender,
+    performance: Preformance,
+    target_event: OutdoorEvent,
+    target_gender: Gender,
+) -> Result<Option<Preformance>, IaafScoringError> {
+    let points = method.score(event, gender, performance)?.points;
+    if points == 0 {
+        return Ok(None);
+    }
+
+    method.performance_for_points(target_event, target_gender, points).map(Some)
+}
+
+/// Converts a performance to the equal-points performance in every event and gender the scoring
+/// method has a table for, in the order of `OutdoorEvent` (male before female).
+///
+/// The table is empty if the performance falls off the table. Events without a table, or whose
+/// table doesn't reach the points, are left out; any other error (e.g. a corrupt table) is returned
+pub fn equivalence_table<M: ScoringMethod<OutdoorEvent> + ?Sized>(
+    method: &mut M,
+    event: OutdoorEvent,
+    gender: Gender,
+    performance: Preformance,
+) -> Result<Vec<Equivalent>, IaafScoringError> {
+    let points = method.score(event, gender, performance)?.points;
+    if points == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut table = Vec::new();
+    for target_event in OutdoorEvent::iter() {
+        for target_gender in Gender::iter() {
+            let performance = match method.performance_for_points(target_event, target_gender, points) {
+                Ok(performance) => performance,
+                // events without a table for the gender, or whose table doesn't reach the points
+                Err(
+                    IaafScoringError::GenderNotEligible { .. }
+                    | IaafScoringError::TableMissing(_)
+                    | IaafScoringError::PointsOutOfRange { .. },
+                ) => continue,
+                Err(e) => return Err(e),
+            };
+
+            table.push(Equivalent {
+                event: target_event,
+                gender: target_gender,
+                performance,
+                points,
+            });
+        }
+    }
+
+    Ok(table)
+}
@@ -1,5 +1,84 @@
-use serde::ser;
+use alloc::string::String;
+use core::fmt::{Display, Formatter};
+use crate::event::MeasurementKind;
+use crate::gender::Gender;
+use crate::preformance::Preformance;
+use crate::Codename;
 
+#[derive(Clone, PartialEq, Debug)]
 pub enum IaafScoringError {
-    Serde(String),
+    /// The codename doesn't belong to any known event
+    UnknownEvent(String),
+
+    /// The event is only contested by the other gender
+    GenderNotEligible { event: String, gender: Gender },
+
+    /// There is no scoring table loaded with this name
+    TableMissing(String),
+
+    /// The preformance couldn't be parsed from the result sheet notation
+    InvalidPreformance(String),
+
+    /// The preformance isn't measured the way the event is (e.g. a distance for a sprint)
+    WrongPreformanceKind { event: String, expected: MeasurementKind, preformance: Preformance },
+
+    /// A combined event was scored with the wrong amount of preformances
+    PreformanceCountMismatch { expected: usize, found: usize },
+
+    /// No entry in the scoring table earns the requested amount of points
+    PointsOutOfRange { event: String, points: u64 },
+
+    /// An archive with scoring tables couldn't be decompressed or unpacked
+    CorruptArchive(String),
+
+    /// A file or directory to read scoring tables from couldn't be read
+    SourceUnreadable { path: String, reason: String },
+
+    /// A row in a scoring table couldn't be parsed, `line` is the line in the CSV file
+    MalformedRow { table: String, line: u64, reason: String },
+
+    /// No scoring curve could be fitted through the table, it has too few distinct rows
+    CurveFitFailed(String),
+
+    /// The wind reading isn't a finite number
+    InvalidWind(f64),
+
+    /// There is no age factor for the age in the event
+    AgeOutOfRange { event: String, age: u8 },
 }
+
+impl Display for IaafScoringError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnknownEvent(code) => write!(f, "unknown event {}", code),
+            Self::GenderNotEligible { event, gender } => {
+                write!(f, "event {} doesn't exist for gender {}", event, gender.to_codename())
+            }
+            Self::TableMissing(table) => write!(f, "scoring table {} is not loaded", table),
+            Self::InvalidPreformance(s) => write!(f, "invalid preformance {}", s),
+            Self::WrongPreformanceKind { event, expected, preformance } => {
+                write!(
+                    f,
+                    "preformance {} can't be scored in event {}, which is measured in {}",
+                    preformance, event, expected
+                )
+            }
+            Self::PreformanceCountMismatch { expected, found } => {
+                write!(f, "expected {} preformances, found {}", expected, found)
+            }
+            Self::PointsOutOfRange { event, points } => {
+                write!(f, "no preformance in event {} earns {} points", event, points)
+            }
+            Self::CorruptArchive(reason) => write!(f, "corrupt table archive: {}", reason),
+            Self::SourceUnreadable { path, reason } => write!(f, "can't read tables from {}: {}", path, reason),
+            Self::MalformedRow { table, line, reason } => {
+                write!(f, "malformed row in {} on line {}: {}", table, line, reason)
+            }
+            Self::CurveFitFailed(table) => write!(f, "can't fit a curve through scoring table {}", table),
+            Self::InvalidWind(wind) => write!(f, "invalid wind reading {}", wind),
+            Self::AgeOutOfRange { event, age } => write!(f, "no age factor in event {} for age {}", event, age),
+        }
+    }
+}
+
+impl core::error::Error for IaafScoringError {}
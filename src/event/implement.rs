@@ -0,0 +1,214 @@
+use core::time::Duration;
+use crate::error::IaafScoringError;
+use crate::gender::Gender;
+use crate::methods::{LookupOutcome, Score, ScoringMethod};
+use crate::preformance::Preformance;
+use super::OutdoorEvent;
+use strum::EnumIter;
+
+/// The lighter implements and lower hurdles used by U18 and U20 athletes. The scoring tables only
+/// cover the senior specifications, so performances are converted to the senior equivalent first
+#[derive(Clone, Copy, Eq, PartialEq, Hash, EnumIter, Debug)]
+pub enum Implement {
+    /// U20 men's shot put
+    Shot6kg,
+    /// U18 boys' shot put
+    Shot5kg,
+    /// U18 girls' shot put
+    Shot3kg,
+    /// U20 men's discus throw
+    Discus1750g,
+    /// U18 boys' discus throw
+    Discus1500g,
+    /// U20 men's hammer throw
+    Hammer6kg,
+    /// U18 boys' hammer throw
+    Hammer5kg,
+    /// U18 girls' hammer throw
+    Hammer3kg,
+    /// U18 boys' javelin throw
+    Javelin700g,
+    /// U18 girls' javelin throw
+    Javelin500g,
+    /// U20 men's 110m hurdles
+    Hurdles99cm,
+    /// U18 boys' 110m hurdles
+    Hurdles91cm,
+    /// U18 girls' 100m hurdles
+    Hurdles76cm,
+    /// U18 boys' 400m hurdles
+    Hurdles400m84cm,
+    /// U18 girls' 400m hurdles, the same height as the senior hurdles
+    Hurdles400m76cm,
+}
+
+/// How a performance with an age group implement is converted to the senior equivalent
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Conversion {
+    /// The distance is multiplied by the factor
+    Factor(f64),
+    /// The time is increased by the amount of seconds
+    Offset(f64),
+}
+
+impl Implement {
+    /// Returns the senior event the implement is a variant of
+    pub fn event(&self) -> OutdoorEvent {
+        match self {
+            Implement::Shot6kg | Implement::Shot5kg | Implement::Shot3kg => OutdoorEvent::ShortPut,
+            Implement::Discus1750g | Implement::Discus1500g => OutdoorEvent::DiscusThrow,
+            Implement::Hammer6kg | Implement::Hammer5kg | Implement::Hammer3kg => OutdoorEvent::HammerThrow,
+            Implement::Javelin700g | Implement::Javelin500g => OutdoorEvent::JavelinThrow,
+            Implement::Hurdles99cm | Implement::Hurdles91cm => OutdoorEvent::Track110mHurdles,
+            Implement::Hurdles76cm => OutdoorEvent::Track100mHurdles,
+            Implement::Hurdles400m84cm | Implement::Hurdles400m76cm => OutdoorEvent::Track400mHurdles,
+        }
+    }
+
+    /// Returns the gender that uses the implement
+    pub fn gender(&self) -> Gender {
+        match self {
+            Implement::Shot3kg
+            | Implement::Hammer3kg
+            | Implement::Javelin500g
+            | Implement::Hurdles76cm
+            | Implement::Hurdles400m76cm => Gender::Female,
+            _ => Gender::Male,
+        }
+    }
+
+    /// Returns the weight of the implement or the height of the hurdles, as on result sheets
+    pub fn specification(&self) -> &'static str {
+        match self {
+            Implement::Shot6kg | Implement::Hammer6kg => "6kg",
+            Implement::Shot5kg | Implement::Hammer5kg => "5kg",
+            Implement::Shot3kg | Implement::Hammer3kg => "3kg",
+            Implement::Discus1750g => "1.75kg",
+            Implement::Discus1500g => "1.5kg",
+            Implement::Javelin700g => "700g",
+            Implement::Javelin500g => "500g",
+            Implement::Hurdles99cm => "99.1cm",
+            Implement::Hurdles91cm => "91.4cm",
+            Implement::Hurdles76cm | Implement::Hurdles400m76cm => "76.2cm",
+            Implement::Hurdles400m84cm => "84cm",
+        }
+    }
+
+    /// Returns the conversion to the senior specification.
+    ///
+    /// World Athletics doesn't publish conversions between age group and senior implements, and the
+    /// scoring tables only have rows for the senior ones. These are this crate's own estimates, so
+    /// scores of converted performances are marked as `LookupOutcome::Estimated`: a lighter
+    /// implement throws further (factor below 1) and lower hurdles are run faster (a positive
+    /// offset), the lighter the implement is relative to the senior one, or the lower the hurdles,
+    /// the bigger the correction. The U18 girls' 400m hurdles have the senior height and aren't
+    /// converted
+    pub fn conversion(&self) -> Conversion {
+        match self {
+            Implement::Shot6kg => Conversion::Factor(0.905),
+            Implement::Shot5kg => Conversion::Factor(0.82),
+            Implement::Shot3kg => Conversion::Factor(0.87),
+            Implement::Discus1750g => Conversion::Factor(0.9),
+            Implement::Discus1500g => Conversion::Factor(0.83),
+            Implement::Hammer6kg => Conversion::Factor(0.89),
+            Implement::Hammer5kg => Conversion::Factor(0.8),
+            Implement::Hammer3kg => Conversion::Factor(0.86),
+            Implement::Javelin700g => Conversion::Factor(0.9),
+            Implement::Javelin500g => Conversion::Factor(0.87),
+            Implement::Hurdles99cm => Conversion::Offset(0.25),
+            Implement::Hurdles91cm => Conversion::Offset(0.5),
+            Implement::Hurdles76cm => Conversion::Offset(0.3),
+            Implement::Hurdles400m84cm => Conversion::Offset(0.6),
+            Implement::Hurdles400m76cm => Conversion::Offset(0.0),
+        }
+    }
+
+    /// Converts a performance with this implement to the equivalent performance with the senior
+    /// implement. Hand times are converted to fully automatic times first, DNF, NM, etc. are
+    /// returned unchanged. Times too long to add the offset to stay at `Duration::MAX`
+    pub fn to_senior(&self, performance: Preformance) -> Preformance {
+        match (self.conversion(), self.event().to_fully_automatic(performance)) {
+            (Conversion::Factor(factor), Preformance::Distance(distance)) => Preformance::Distance(distance * factor),
+            (Conversion::Offset(seconds), Preformance::Time(time)) => {
+                Preformance::Time(time.checked_add(Duration::from_secs_f64(seconds)).unwrap_or(Duration::MAX))
+            }
+            (_, performance) => performance,
+        }
+    }
+
+    /// Scores the performance with the tables of the senior event, after converting it with
+    /// `to_senior`. The performance in the score is the senior equivalent, and the outcome is
+    /// `LookupOutcome::Estimated`, as the conversion isn't official (see `conversion`)
+    pub fn score<M: ScoringMethod<OutdoorEvent> + ?Sized>(
+        &self,
+        method: &mut M,
+        performance: Preformance,
+    ) -> Result<Score<OutdoorEvent>, IaafScoringError> {
+        let score = method.score(self.event(), self.gender(), self.to_senior(performance))?;
+        Ok(Score {
+            outcome: LookupOutcome::Estimated,
+            ..score
+        })
+    }
+}
+
+#[test]
+fn test_implement_conversion() {
+    use strum::IntoEnumIterator;
+
+    // the senior equivalent is never better than the performance with the age group implement
+    for implement in Implement::iter() {
+        assert!(implement.event().exists_for_gender(&implement.gender()));
+        match implement.conversion() {
+            Conversion::Factor(factor) => assert!(factor > 0.0 && factor < 1.0, "{:?}", implement),
+            Conversion::Offset(seconds) => assert!(seconds >= 0.0, "{:?}", implement),
+        }
+    }
+
+    // the lighter the implement or the lower the hurdles, the bigger the correction
+    let factor = |implement: Implement| match implement.to_senior(Preformance::Distance(10.0)) {
+        Preformance::Distance(distance) => distance,
+        performance => panic!("{:?} converted to {:?}", implement, performance),
+    };
+    assert!(factor(Implement::Shot5kg) < factor(Implement::Shot6kg));
+    assert!(factor(Implement::Discus1500g) < factor(Implement::Discus1750g));
+    assert!(factor(Implement::Hammer5kg) < factor(Implement::Hammer6kg));
+    // 500g is a sixth lighter than the women's javelin, 700g only an eighth lighter than the men's
+    assert!(factor(Implement::Javelin500g) < factor(Implement::Javelin700g));
+
+    let time = |millis| Preformance::Time(Duration::from_millis(millis));
+    let offset = |implement: Implement, millis| match implement.to_senior(time(millis)) {
+        Preformance::Time(senior) => senior - Duration::from_millis(millis),
+        performance => panic!("{:?} converted to {:?}", implement, performance),
+    };
+    assert!(offset(Implement::Hurdles91cm, 13500) > offset(Implement::Hurdles99cm, 13500));
+    assert!(offset(Implement::Hurdles400m84cm, 54000) > Duration::ZERO);
+    assert_eq!(Implement::Hurdles400m76cm.to_senior(time(60000)), time(60000));
+    assert_eq!(
+        Implement::Hurdles99cm.to_senior(Preformance::Time(Duration::MAX)),
+        Preformance::Time(Duration::MAX)
+    );
+
+    // hand times are converted to fully automatic times before the offset is added
+    assert_eq!(
+        Implement::Hurdles99cm.to_senior(Preformance::HandTime(Duration::from_millis(13500))),
+        Implement::Hurdles99cm.to_senior(time(13740))
+    );
+
+    let nm = Preformance::NoResult(crate::preformance::NonPreformance::Nm);
+    assert_eq!(Implement::Javelin700g.to_senior(nm), nm);
+}
+
+#[test]
+fn test_implement_score_is_estimated() {
+    use crate::event::EventCompareType;
+    use crate::methods::formula::{CurveCoefficients, FormulaScoring};
+
+    let curve = CurveCoefficients { a: 7.0, b: -26.0, c: 0.0 };
+    let mut method = FormulaScoring::new();
+    method.insert(OutdoorEvent::Track110mHurdles, Gender::Male, curve);
+
+    let score = Implement::Hurdles99cm.score(&mut method, Preformance::Time(Duration::from_millis(13500))).unwrap();
+    assert_eq!(score.outcome, LookupOutcome::Estimated);
+    assert_eq!(score.points, curve.points(13.75, EventCompareType::SmallerIsBetter).floor() as u64);
+}
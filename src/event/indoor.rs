@@ -0,0 +1,310 @@
+use alloc::string::{String, ToString};
+use crate::error::IaafScoringError;
+use crate::gender::Gender;
+use crate::Codename;
+use super::{EventCompareType, MeasurementKind};
+use crate::preformance::{Preformance, TimePrecision};
+use core::str::FromStr;
+use core::time::Duration;
+use strum::EnumIter;
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, EnumIter, Debug)]
+pub enum IndoorEvent {
+    // Non-running events
+    HighJump,
+    LongJump,
+    TripleJump,
+    PoleVault,
+    ShortPut,
+
+    /// This event only has scoring tables for female contestants
+    Pentathlon,
+    /// This event only has scoring tables for male contestants
+    Heptathlon,
+
+    // Sprint events
+    Track50m,
+    Track55m,
+    Track60m,
+    Track200m,
+    Track300m,
+    Track400m,
+    Track500m,
+
+    // Sprint events with hurdles
+    Track50mHurdles,
+    Track55mHurdles,
+    Track60mHurdles,
+
+    // Relay sprint events
+    Track4x200mRelay,
+    Track4x400mRelay,
+
+    // Medium-distance events
+    Track600m,
+    Track800m,
+    Track1000m,
+    Track1500m,
+    Track1Mile,
+    Track2000m,
+    Track3000m,
+    Track2Miles,
+    Track5000m,
+
+    // Walking events
+    Walk3000m,
+    Walk5000m,
+}
+
+impl Codename for IndoorEvent {
+    fn to_codename(&self) -> &str {
+        match self {
+            IndoorEvent::HighJump => "HJ",
+            IndoorEvent::LongJump => "LJ",
+            IndoorEvent::TripleJump => "TJ",
+            IndoorEvent::PoleVault => "PV",
+            IndoorEvent::ShortPut => "SP",
+            IndoorEvent::Pentathlon => "Pentathlon",
+            IndoorEvent::Heptathlon => "Heptathlon",
+            IndoorEvent::Track50m => "50m",
+            IndoorEvent::Track55m => "55m",
+            IndoorEvent::Track60m => "60m",
+            IndoorEvent::Track200m => "200m",
+            IndoorEvent::Track300m => "300m",
+            IndoorEvent::Track400m => "400m",
+            IndoorEvent::Track500m => "500m",
+            IndoorEvent::Track50mHurdles => "50mH",
+            IndoorEvent::Track55mHurdles => "55mH",
+            IndoorEvent::Track60mHurdles => "60mH",
+            IndoorEvent::Track4x200mRelay => "4x200m",
+            IndoorEvent::Track4x400mRelay => "4x400m",
+            IndoorEvent::Track600m => "600m",
+            IndoorEvent::Track800m => "800m",
+            IndoorEvent::Track1000m => "1000m",
+            IndoorEvent::Track1500m => "1500m",
+            IndoorEvent::Track1Mile => "Mile",
+            IndoorEvent::Track2000m => "2000m",
+            IndoorEvent::Track3000m => "3000m",
+            IndoorEvent::Track2Miles => "2 Miles",
+            IndoorEvent::Track5000m => "5000m",
+            IndoorEvent::Walk3000m => "3000mW",
+            IndoorEvent::Walk5000m => "5000mW",
+        }
+    }
+
+    fn from_codename(code: &str) -> Option<Self> {
+        let event = match code {
+            "HJ" => IndoorEvent::HighJump,
+            "LJ" => IndoorEvent::LongJump,
+            "TJ" => IndoorEvent::TripleJump,
+            "PV" => IndoorEvent::PoleVault,
+            "SP" => IndoorEvent::ShortPut,
+            "Pentathlon" => IndoorEvent::Pentathlon,
+            "Heptathlon" => IndoorEvent::Heptathlon,
+            "50m" => IndoorEvent::Track50m,
+            "55m" => IndoorEvent::Track55m,
+            "60m" => IndoorEvent::Track60m,
+            "200m" => IndoorEvent::Track200m,
+            "300m" => IndoorEvent::Track300m,
+            "400m" => IndoorEvent::Track400m,
+            "500m" => IndoorEvent::Track500m,
+            "50mH" => IndoorEvent::Track50mHurdles,
+            "55mH" => IndoorEvent::Track55mHurdles,
+            "60mH" => IndoorEvent::Track60mHurdles,
+            "4x200m" => IndoorEvent::Track4x200mRelay,
+            "4x400m" => IndoorEvent::Track4x400mRelay,
+            "600m" => IndoorEvent::Track600m,
+            "800m" => IndoorEvent::Track800m,
+            "1000m" => IndoorEvent::Track1000m,
+            "1500m" => IndoorEvent::Track1500m,
+            "Mile" => IndoorEvent::Track1Mile,
+            "2000m" => IndoorEvent::Track2000m,
+            "3000m" => IndoorEvent::Track3000m,
+            "2 Miles" => IndoorEvent::Track2Miles,
+            "5000m" => IndoorEvent::Track5000m,
+            "3000mW" => IndoorEvent::Walk3000m,
+            "5000mW" => IndoorEvent::Walk5000m,
+            _ => return None,
+        };
+
+        Some(event)
+    }
+}
+
+impl FromStr for IndoorEvent {
+    type Err = IaafScoringError;
+
+    /// Parses the codename of the event, as used in the names of the scoring tables
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_codename(s).ok_or_else(|| IaafScoringError::UnknownEvent(s.to_string()))
+    }
+}
+
+impl IndoorEvent {
+    pub fn is_gender_specific(&self) -> bool {
+        self.exclusive_for_gender().is_some()
+    }
+
+    pub fn exclusive_for_gender(&self) -> Option<Gender> {
+        match self {
+            IndoorEvent::Pentathlon => Some(Gender::Female),
+            IndoorEvent::Heptathlon => Some(Gender::Male),
+            _ => None,
+        }
+    }
+
+    pub fn exists_for_gender(&self, gender: &Gender) -> bool {
+        match self.exclusive_for_gender() {
+            None => true,
+            Some(specific_gender) => &specific_gender == gender,
+        }
+    }
+
+    /// Builds a preformance of the kind this event is measured in, from a number as stored in the
+    /// scoring tables (meters, seconds or points)
+    pub fn preformance(&self, number: f64) -> Preformance {
+        Preformance::from_number(self.measurement_kind(), number)
+    }
+
+    /// Parses a preformance in this event from the notation of official result sheets, see
+    /// `Preformance::parse`
+    pub fn parse_preformance(&self, s: &str) -> Result<Preformance, IaafScoringError> {
+        Preformance::parse(s, self.measurement_kind())
+    }
+
+    /// Formats a preformance in this event the way official result sheets do, see
+    /// `Preformance::to_result_string`
+    pub fn format_preformance(&self, performance: Preformance) -> String {
+        performance.to_result_string(TimePrecision::Hundredths)
+    }
+
+    pub fn measurement_kind(&self) -> MeasurementKind {
+        match self {
+            IndoorEvent::HighJump => MeasurementKind::Distance,
+            IndoorEvent::LongJump => MeasurementKind::Distance,
+            IndoorEvent::TripleJump => MeasurementKind::Distance,
+            IndoorEvent::PoleVault => MeasurementKind::Distance,
+            IndoorEvent::ShortPut => MeasurementKind::Distance,
+            IndoorEvent::Pentathlon => MeasurementKind::Points,
+            IndoorEvent::Heptathlon => MeasurementKind::Points,
+
+            IndoorEvent::Track50m => MeasurementKind::Time,
+            IndoorEvent::Track55m => MeasurementKind::Time,
+            IndoorEvent::Track60m => MeasurementKind::Time,
+            IndoorEvent::Track200m => MeasurementKind::Time,
+            IndoorEvent::Track300m => MeasurementKind::Time,
+            IndoorEvent::Track400m => MeasurementKind::Time,
+            IndoorEvent::Track500m => MeasurementKind::Time,
+            IndoorEvent::Track50mHurdles => MeasurementKind::Time,
+            IndoorEvent::Track55mHurdles => MeasurementKind::Time,
+            IndoorEvent::Track60mHurdles => MeasurementKind::Time,
+            IndoorEvent::Track4x200mRelay => MeasurementKind::Time,
+            IndoorEvent::Track4x400mRelay => MeasurementKind::Time,
+            IndoorEvent::Track600m => MeasurementKind::Time,
+            IndoorEvent::Track800m => MeasurementKind::Time,
+            IndoorEvent::Track1000m => MeasurementKind::Time,
+            IndoorEvent::Track1500m => MeasurementKind::Time,
+            IndoorEvent::Track1Mile => MeasurementKind::Time,
+            IndoorEvent::Track2000m => MeasurementKind::Time,
+            IndoorEvent::Track3000m => MeasurementKind::Time,
+            IndoorEvent::Track2Miles => MeasurementKind::Time,
+            IndoorEvent::Track5000m => MeasurementKind::Time,
+            IndoorEvent::Walk3000m => MeasurementKind::Time,
+            IndoorEvent::Walk5000m => MeasurementKind::Time,
+        }
+    }
+
+    /// Returns the time added to hand times to convert them to fully automatic times, or `None` if
+    /// hand times in this event aren't converted. The same corrections as for the outdoor sprints
+    pub fn hand_timing_correction(&self) -> Option<Duration> {
+        match self {
+            IndoorEvent::Track50m
+            | IndoorEvent::Track55m
+            | IndoorEvent::Track60m
+            | IndoorEvent::Track200m
+            | IndoorEvent::Track50mHurdles
+            | IndoorEvent::Track55mHurdles
+            | IndoorEvent::Track60mHurdles
+            | IndoorEvent::Track4x200mRelay => Some(Duration::from_millis(240)),
+            IndoorEvent::Track300m
+            | IndoorEvent::Track400m
+            | IndoorEvent::Track500m
+            | IndoorEvent::Track4x400mRelay => Some(Duration::from_millis(140)),
+            _ => None,
+        }
+    }
+
+    /// Converts a hand time to the fully automatic time it is scored as, see
+    /// `hand_timing_correction`. Hand times in longer races are taken as they are, other
+    /// preformances are returned unchanged. Times too long to correct stay at `Duration::MAX`
+    pub fn to_fully_automatic(&self, performance: Preformance) -> Preformance {
+        match performance {
+            Preformance::HandTime(duration) => {
+                Preformance::Time(duration.saturating_add(self.hand_timing_correction().unwrap_or_default()))
+            }
+            performance => performance,
+        }
+    }
+
+    pub fn compare_type(&self) -> EventCompareType {
+        match self {
+            IndoorEvent::HighJump => EventCompareType::GreaterIsBetter,
+            IndoorEvent::LongJump => EventCompareType::GreaterIsBetter,
+            IndoorEvent::TripleJump => EventCompareType::GreaterIsBetter,
+            IndoorEvent::PoleVault => EventCompareType::GreaterIsBetter,
+            IndoorEvent::ShortPut => EventCompareType::GreaterIsBetter,
+            IndoorEvent::Pentathlon => EventCompareType::GreaterIsBetter,
+            IndoorEvent::Heptathlon => EventCompareType::GreaterIsBetter,
+
+            IndoorEvent::Track50m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track55m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track60m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track200m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track300m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track400m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track500m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track50mHurdles => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track55mHurdles => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track60mHurdles => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track4x200mRelay => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track4x400mRelay => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track600m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track800m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track1000m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track1500m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track1Mile => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track2000m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track3000m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track2Miles => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Track5000m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Walk3000m => EventCompareType::SmallerIsBetter,
+            IndoorEvent::Walk5000m => EventCompareType::SmallerIsBetter,
+        }
+    }
+}
+
+#[test]
+fn test_gender_specific_events() {
+    assert!(IndoorEvent::Pentathlon.exists_for_gender(&Gender::Female));
+    assert!(!IndoorEvent::Pentathlon.exists_for_gender(&Gender::Male));
+    assert!(IndoorEvent::Heptathlon.exists_for_gender(&Gender::Male));
+    assert!(!IndoorEvent::Heptathlon.exists_for_gender(&Gender::Female));
+
+    assert!(!IndoorEvent::Track60mHurdles.is_gender_specific());
+    assert!(IndoorEvent::Track60m.exists_for_gender(&Gender::Female));
+    assert!(IndoorEvent::Track60m.exists_for_gender(&Gender::Male));
+}
+
+#[test]
+fn test_hand_timing() {
+    let hand = |millis| Preformance::HandTime(Duration::from_millis(millis));
+    let time = |millis| Preformance::Time(Duration::from_millis(millis));
+
+    assert_eq!(IndoorEvent::Track60m.to_fully_automatic(hand(6800)), time(7040));
+    assert_eq!(IndoorEvent::Track400m.to_fully_automatic(hand(48000)), time(48140));
+    assert_eq!(IndoorEvent::Track1500m.to_fully_automatic(hand(240000)), time(240000));
+    assert_eq!(
+        IndoorEvent::Track60m.to_fully_automatic(Preformance::HandTime(Duration::MAX)),
+        Preformance::Time(Duration::MAX)
+    );
+}
@@ -1,8 +1,126 @@
+pub mod implement;
+pub mod indoor;
 pub mod outdoor;
 
+pub use implement::Implement;
+pub use indoor::IndoorEvent;
 pub use outdoor::OutdoorEvent;
 
+use core::fmt::{Display, Formatter};
+use core::hash::Hash;
+use crate::gender::Gender;
+use crate::methods::Edition;
+use crate::preformance::Preformance;
+use crate::Codename;
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
 pub enum EventCompareType {
     GreaterIsBetter,
     SmallerIsBetter,
-}
\ No newline at end of file
+}
+
+/// How the preformances of an event are measured
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum MeasurementKind {
+    /// Measured in seconds (100m, 400mH, Marathon, etc.)
+    Time,
+    /// Measured in meters (HJ, LJ, etc.)
+    Distance,
+    /// Measured in points (Decathlon, Heptathlon, etc.)
+    Points,
+}
+
+impl Display for MeasurementKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MeasurementKind::Time => write!(f, "time"),
+            MeasurementKind::Distance => write!(f, "distance"),
+            MeasurementKind::Points => write!(f, "points"),
+        }
+    }
+}
+
+/// What the scoring tables need to know about an event, so outdoor and indoor events can be scored
+/// by the same scoring method
+pub trait TableEvent: Copy + Eq + Hash {
+    /// Where the event is contested, as in the names of the tables: `Outdoor` or `Indoor`
+    const LOCATION: &'static str;
+
+    /// Returns the codename of the event, as in the names of the tables
+    fn codename(&self) -> &str;
+    fn measurement_kind(&self) -> MeasurementKind;
+    fn compare_type(&self) -> EventCompareType;
+    fn exists_for_gender(&self, gender: &Gender) -> bool;
+    /// Whether the edition has a scoring table for this event and gender
+    fn exists_in_edition(&self, edition: Edition, gender: &Gender) -> bool;
+    /// Builds a preformance of the kind this event is measured in, from a number as stored in the
+    /// scoring tables
+    fn preformance(&self, number: f64) -> Preformance;
+    /// Converts a hand time to the fully automatic time the tables are made for
+    fn to_fully_automatic(&self, performance: Preformance) -> Preformance;
+}
+
+impl TableEvent for OutdoorEvent {
+    const LOCATION: &'static str = "Outdoor";
+
+    fn codename(&self) -> &str {
+        self.to_codename()
+    }
+
+    fn measurement_kind(&self) -> MeasurementKind {
+        OutdoorEvent::measurement_kind(self)
+    }
+
+    fn compare_type(&self) -> EventCompareType {
+        OutdoorEvent::compare_type(self)
+    }
+
+    fn exists_for_gender(&self, gender: &Gender) -> bool {
+        OutdoorEvent::exists_for_gender(self, gender)
+    }
+
+    fn exists_in_edition(&self, edition: Edition, gender: &Gender) -> bool {
+        OutdoorEvent::exists_in_edition(self, edition, gender)
+    }
+
+    fn preformance(&self, number: f64) -> Preformance {
+        OutdoorEvent::preformance(self, number)
+    }
+
+    fn to_fully_automatic(&self, performance: Preformance) -> Preformance {
+        OutdoorEvent::to_fully_automatic(self, performance)
+    }
+}
+
+impl TableEvent for IndoorEvent {
+    const LOCATION: &'static str = "Indoor";
+
+    fn codename(&self) -> &str {
+        self.to_codename()
+    }
+
+    fn measurement_kind(&self) -> MeasurementKind {
+        IndoorEvent::measurement_kind(self)
+    }
+
+    fn compare_type(&self) -> EventCompareType {
+        IndoorEvent::compare_type(self)
+    }
+
+    fn exists_for_gender(&self, gender: &Gender) -> bool {
+        IndoorEvent::exists_for_gender(self, gender)
+    }
+
+    /// Both editions have tables for every indoor event
+    fn exists_in_edition(&self, _edition: Edition, gender: &Gender) -> bool {
+        IndoorEvent::exists_for_gender(self, gender)
+    }
+
+    fn preformance(&self, number: f64) -> Preformance {
+        IndoorEvent::preformance(self, number)
+    }
+
+    fn to_fully_automatic(&self, performance: Preformance) -> Preformance {
+        IndoorEvent::to_fully_automatic(self, performance)
+    }
+}
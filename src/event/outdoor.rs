@@ -1,9 +1,15 @@
+use alloc::string::{String, ToString};
+use crate::error::IaafScoringError;
 use crate::gender::Gender;
 use crate::Codename;
-use super::EventCompareType;
+use super::{EventCompareType, MeasurementKind};
+use crate::methods::Edition;
+use crate::preformance::{Preformance, TimePrecision};
+use core::str::FromStr;
+use core::time::Duration;
 use strum::EnumIter;
 
-#[derive(Clone, Copy, Eq, PartialEq, Hash, EnumIter, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, EnumIter, Debug)]
 pub enum OutdoorEvent {
     // Non-running events
     HighJump,
@@ -40,6 +46,8 @@ pub enum OutdoorEvent {
     Track4x100mRelay,
     Track4x200mRelay,
     Track4x400mRelay,
+    /// This event was introduced in the WA 2022 tables
+    Track4x400mMixedRelay,
 
     // Medium-distance events
     Track600m,
@@ -54,6 +62,8 @@ pub enum OutdoorEvent {
     Track10000m,
 
     // Long-distance events (non-track)
+    /// This event was introduced in the WA 2022 tables
+    RoadMile,
     Road5km,
     Road10km,
     Road15km,
@@ -113,6 +123,7 @@ impl Codename for OutdoorEvent {
             OutdoorEvent::Track4x100mRelay => "4x100m",
             OutdoorEvent::Track4x200mRelay => "4x200m",
             OutdoorEvent::Track4x400mRelay => "4x400m",
+            OutdoorEvent::Track4x400mMixedRelay => "4x400m Mix",
             OutdoorEvent::Track600m => "600m",
             OutdoorEvent::Track800m => "800m",
             OutdoorEvent::Track1000m => "1000m",
@@ -123,6 +134,7 @@ impl Codename for OutdoorEvent {
             OutdoorEvent::Track2000m => "2000m",
             OutdoorEvent::Track5000m => "5000m",
             OutdoorEvent::Track10000m => "10000m",
+            OutdoorEvent::RoadMile => "Road Mile",
             OutdoorEvent::Road5km => "5 km",
             OutdoorEvent::Road10km => "10 km",
             OutdoorEvent::Road15km => "15 km",
@@ -177,6 +189,7 @@ impl Codename for OutdoorEvent {
             "4x100m" => OutdoorEvent::Track4x100mRelay,
             "4x200m" => OutdoorEvent::Track4x200mRelay,
             "4x400m" => OutdoorEvent::Track4x400mRelay,
+            "4x400m Mix" => OutdoorEvent::Track4x400mMixedRelay,
             "600m" => OutdoorEvent::Track600m,
             "800m" => OutdoorEvent::Track800m,
             "1000m" => OutdoorEvent::Track1000m,
@@ -187,6 +200,7 @@ impl Codename for OutdoorEvent {
             "2000m" => OutdoorEvent::Track2000m,
             "5000m" => OutdoorEvent::Track5000m,
             "10000m" => OutdoorEvent::Track10000m,
+            "Road Mile" => OutdoorEvent::RoadMile,
             "5 km" => OutdoorEvent::Road5km,
             "10 km" => OutdoorEvent::Road10km,
             "15 km" => OutdoorEvent::Road15km,
@@ -220,6 +234,15 @@ impl Codename for OutdoorEvent {
     }
 }
 
+impl FromStr for OutdoorEvent {
+    type Err = IaafScoringError;
+
+    /// Parses the codename of the event, as used in the names of the scoring tables
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_codename(s).ok_or_else(|| IaafScoringError::UnknownEvent(s.to_string()))
+    }
+}
+
 impl OutdoorEvent {
     pub fn is_gender_specific(&self) -> bool {
         self.exclusive_for_gender().is_some()
@@ -231,7 +254,6 @@ impl OutdoorEvent {
             OutdoorEvent::Track100mHurdles => Some(Gender::Female),
             OutdoorEvent::Heptathlon => Some(Gender::Female),
             OutdoorEvent::Decathlon => Some(Gender::Male),
-            OutdoorEvent::Walk35km => Some(Gender::Male),
             _ => None,
         }
     }
@@ -243,6 +265,180 @@ impl OutdoorEvent {
         }
     }
 
+    /// Whether the edition has a scoring table for this event and gender
+    pub fn exists_in_edition(&self, edition: Edition, gender: &Gender) -> bool {
+        if !self.exists_for_gender(gender) {
+            return false;
+        }
+
+        match (self, edition) {
+            (OutdoorEvent::Track4x400mMixedRelay, Edition::Iaaf2017) => false,
+            (OutdoorEvent::RoadMile, Edition::Iaaf2017) => false,
+            // The 35km walk was only scored for men before the 2022 edition
+            (OutdoorEvent::Walk35km, Edition::Iaaf2017) => gender == &Gender::Male,
+            _ => true,
+        }
+    }
+
+    /// Builds a preformance of the kind this event is measured in, from a number as stored in the
+    /// scoring tables (meters, seconds or points)
+    pub fn preformance(&self, number: f64) -> Preformance {
+        Preformance::from_number(self.measurement_kind(), number)
+    }
+
+    /// Parses a preformance in this event from the notation of official result sheets, see
+    /// `Preformance::parse`
+    pub fn parse_preformance(&self, s: &str) -> Result<Preformance, IaafScoringError> {
+        Preformance::parse(s, self.measurement_kind())
+    }
+
+    /// Formats a preformance in this event the way official result sheets do, see
+    /// `Preformance::to_result_string`
+    pub fn format_preformance(&self, performance: Preformance) -> String {
+        performance.to_result_string(self.time_precision())
+    }
+
+    /// Returns how precisely times are rounded in this event, races outside of the stadium are
+    /// rounded to whole seconds
+    pub fn time_precision(&self) -> TimePrecision {
+        match self {
+            OutdoorEvent::RoadMile
+            | OutdoorEvent::Road5km
+            | OutdoorEvent::Road10km
+            | OutdoorEvent::Road15km
+            | OutdoorEvent::Road20km
+            | OutdoorEvent::Road25km
+            | OutdoorEvent::Road30km
+            | OutdoorEvent::Road100km
+            | OutdoorEvent::Road10Miles
+            | OutdoorEvent::HalfMarathon
+            | OutdoorEvent::Marathon
+            | OutdoorEvent::Walk3km
+            | OutdoorEvent::Walk5km
+            | OutdoorEvent::Walk10km
+            | OutdoorEvent::Walk15km
+            | OutdoorEvent::Walk20km
+            | OutdoorEvent::Walk30km
+            | OutdoorEvent::Walk35km
+            | OutdoorEvent::Walk50km => TimePrecision::Seconds,
+            _ => TimePrecision::Hundredths,
+        }
+    }
+
+    /// Returns the time added to hand times to convert them to fully automatic times, or `None` if
+    /// hand times in this event aren't converted
+    pub fn hand_timing_correction(&self) -> Option<Duration> {
+        match self {
+            OutdoorEvent::Track100m
+            | OutdoorEvent::Track200m
+            | OutdoorEvent::Track100mHurdles
+            | OutdoorEvent::Track110mHurdles
+            | OutdoorEvent::Track4x100mRelay
+            | OutdoorEvent::Track4x200mRelay => Some(Duration::from_millis(240)),
+            OutdoorEvent::Track300m
+            | OutdoorEvent::Track400m
+            | OutdoorEvent::Track400mHurdles
+            | OutdoorEvent::Track4x400mRelay
+            | OutdoorEvent::Track4x400mMixedRelay => Some(Duration::from_millis(140)),
+            _ => None,
+        }
+    }
+
+    /// Converts a hand time to the fully automatic time it is scored as, see
+    /// `hand_timing_correction`. Hand times in longer races are taken as they are, other
+    /// preformances are returned unchanged. Times too long to correct stay at `Duration::MAX`
+    pub fn to_fully_automatic(&self, performance: Preformance) -> Preformance {
+        match performance {
+            Preformance::HandTime(duration) => {
+                Preformance::Time(duration.saturating_add(self.hand_timing_correction().unwrap_or_default()))
+            }
+            performance => performance,
+        }
+    }
+
+    /// Returns the wind modification in points per m/s of wind, or `None` if the wind isn't measured
+    /// in this event. The rates are those of the wind modification table of the World Athletics
+    /// Rankings rules: 6 points per m/s for the 100m, the short hurdles and the horizontal jumps, 3
+    /// points per m/s for the 200m
+    pub fn wind_modification_rate(&self) -> Option<f64> {
+        match self {
+            OutdoorEvent::Track100m
+            | OutdoorEvent::Track100mHurdles
+            | OutdoorEvent::Track110mHurdles
+            | OutdoorEvent::LongJump
+            | OutdoorEvent::TripleJump => Some(6.0),
+            OutdoorEvent::Track200m => Some(3.0),
+            _ => None,
+        }
+    }
+
+    pub fn measurement_kind(&self) -> MeasurementKind {
+        match self {
+            OutdoorEvent::HighJump => MeasurementKind::Distance,
+            OutdoorEvent::LongJump => MeasurementKind::Distance,
+            OutdoorEvent::TripleJump => MeasurementKind::Distance,
+            OutdoorEvent::DiscusThrow => MeasurementKind::Distance,
+            OutdoorEvent::HammerThrow => MeasurementKind::Distance,
+            OutdoorEvent::JavelinThrow => MeasurementKind::Distance,
+            OutdoorEvent::PoleVault => MeasurementKind::Distance,
+            OutdoorEvent::ShortPut => MeasurementKind::Distance,
+            OutdoorEvent::Heptathlon => MeasurementKind::Points,
+            OutdoorEvent::Decathlon => MeasurementKind::Points,
+
+            OutdoorEvent::Track100m => MeasurementKind::Time,
+            OutdoorEvent::Track200m => MeasurementKind::Time,
+            OutdoorEvent::Track300m => MeasurementKind::Time,
+            OutdoorEvent::Track400m => MeasurementKind::Time,
+            OutdoorEvent::Track500m => MeasurementKind::Time,
+            OutdoorEvent::Track100mHurdles => MeasurementKind::Time,
+            OutdoorEvent::Track110mHurdles => MeasurementKind::Time,
+            OutdoorEvent::Track400mHurdles => MeasurementKind::Time,
+            OutdoorEvent::Track2kmSteeplechase => MeasurementKind::Time,
+            OutdoorEvent::Track3kmSteeplechase => MeasurementKind::Time,
+            OutdoorEvent::Track4x100mRelay => MeasurementKind::Time,
+            OutdoorEvent::Track4x200mRelay => MeasurementKind::Time,
+            OutdoorEvent::Track4x400mRelay => MeasurementKind::Time,
+            OutdoorEvent::Track4x400mMixedRelay => MeasurementKind::Time,
+            OutdoorEvent::Track600m => MeasurementKind::Time,
+            OutdoorEvent::Track800m => MeasurementKind::Time,
+            OutdoorEvent::Track1000m => MeasurementKind::Time,
+            OutdoorEvent::Track1500m => MeasurementKind::Time,
+            OutdoorEvent::Track3000m => MeasurementKind::Time,
+            OutdoorEvent::Track1Mile => MeasurementKind::Time,
+            OutdoorEvent::Track2Miles => MeasurementKind::Time,
+            OutdoorEvent::Track2000m => MeasurementKind::Time,
+            OutdoorEvent::Track5000m => MeasurementKind::Time,
+            OutdoorEvent::Track10000m => MeasurementKind::Time,
+            OutdoorEvent::RoadMile => MeasurementKind::Time,
+            OutdoorEvent::Road5km => MeasurementKind::Time,
+            OutdoorEvent::Road10km => MeasurementKind::Time,
+            OutdoorEvent::Road15km => MeasurementKind::Time,
+            OutdoorEvent::Road20km => MeasurementKind::Time,
+            OutdoorEvent::Road25km => MeasurementKind::Time,
+            OutdoorEvent::Road30km => MeasurementKind::Time,
+            OutdoorEvent::Road100km => MeasurementKind::Time,
+            OutdoorEvent::Road10Miles => MeasurementKind::Time,
+            OutdoorEvent::HalfMarathon => MeasurementKind::Time,
+            OutdoorEvent::Marathon => MeasurementKind::Time,
+            OutdoorEvent::Walk3km => MeasurementKind::Time,
+            OutdoorEvent::Walk5km => MeasurementKind::Time,
+            OutdoorEvent::Walk10km => MeasurementKind::Time,
+            OutdoorEvent::Walk15km => MeasurementKind::Time,
+            OutdoorEvent::Walk20km => MeasurementKind::Time,
+            OutdoorEvent::Walk30km => MeasurementKind::Time,
+            OutdoorEvent::Walk50km => MeasurementKind::Time,
+            OutdoorEvent::Walk35km => MeasurementKind::Time,
+            OutdoorEvent::Walk3kmTrack => MeasurementKind::Time,
+            OutdoorEvent::Walk5kmTrack => MeasurementKind::Time,
+            OutdoorEvent::Walk10kmTrack => MeasurementKind::Time,
+            OutdoorEvent::Walk15kmTrack => MeasurementKind::Time,
+            OutdoorEvent::Walk20kmTrack => MeasurementKind::Time,
+            OutdoorEvent::Walk30kmTrack => MeasurementKind::Time,
+            OutdoorEvent::Walk35kmTrack => MeasurementKind::Time,
+            OutdoorEvent::Walk50kmTrack => MeasurementKind::Time,
+        }
+    }
+
     pub fn compare_type(&self) -> EventCompareType {
         match self {
             OutdoorEvent::HighJump => EventCompareType::GreaterIsBetter,
@@ -269,6 +465,7 @@ impl OutdoorEvent {
             OutdoorEvent::Track4x100mRelay => EventCompareType::SmallerIsBetter,
             OutdoorEvent::Track4x200mRelay => EventCompareType::SmallerIsBetter,
             OutdoorEvent::Track4x400mRelay => EventCompareType::SmallerIsBetter,
+            OutdoorEvent::Track4x400mMixedRelay => EventCompareType::SmallerIsBetter,
             OutdoorEvent::Track600m => EventCompareType::SmallerIsBetter,
             OutdoorEvent::Track800m => EventCompareType::SmallerIsBetter,
             OutdoorEvent::Track1000m => EventCompareType::SmallerIsBetter,
@@ -279,6 +476,7 @@ impl OutdoorEvent {
             OutdoorEvent::Track2000m => EventCompareType::SmallerIsBetter,
             OutdoorEvent::Track5000m => EventCompareType::SmallerIsBetter,
             OutdoorEvent::Track10000m => EventCompareType::SmallerIsBetter,
+            OutdoorEvent::RoadMile => EventCompareType::SmallerIsBetter,
             OutdoorEvent::Road5km => EventCompareType::SmallerIsBetter,
             OutdoorEvent::Road10km => EventCompareType::SmallerIsBetter,
             OutdoorEvent::Road15km => EventCompareType::SmallerIsBetter,
@@ -319,3 +517,60 @@ fn test_gender_specific_events() {
     assert!(OutdoorEvent::LongJump.exists_for_gender(&Gender::Female));
     assert!(OutdoorEvent::LongJump.exists_for_gender(&Gender::Male));
 }
+
+#[test]
+fn test_hand_timing() {
+    let hand = |millis| Preformance::HandTime(Duration::from_millis(millis));
+    let time = |millis| Preformance::Time(Duration::from_millis(millis));
+
+    assert_eq!(OutdoorEvent::Track100m.to_fully_automatic(hand(10800)), time(11040));
+    assert_eq!(OutdoorEvent::Track110mHurdles.to_fully_automatic(hand(14000)), time(14240));
+    assert_eq!(OutdoorEvent::Track400m.to_fully_automatic(hand(48000)), time(48140));
+    assert_eq!(OutdoorEvent::Track1500m.to_fully_automatic(hand(240000)), time(240000));
+    assert_eq!(OutdoorEvent::Track100m.to_fully_automatic(time(10800)), time(10800));
+    assert_eq!(
+        OutdoorEvent::Track100m.to_fully_automatic(Preformance::HandTime(Duration::MAX)),
+        Preformance::Time(Duration::MAX)
+    );
+}
+
+#[test]
+fn test_events_per_edition() {
+    assert!(OutdoorEvent::Walk35km.exists_in_edition(Edition::Iaaf2017, &Gender::Male));
+    assert!(!OutdoorEvent::Walk35km.exists_in_edition(Edition::Iaaf2017, &Gender::Female));
+    assert!(OutdoorEvent::Walk35km.exists_in_edition(Edition::Wa2022, &Gender::Female));
+
+    assert!(!OutdoorEvent::RoadMile.exists_in_edition(Edition::Iaaf2017, &Gender::Male));
+    assert!(OutdoorEvent::RoadMile.exists_in_edition(Edition::Wa2022, &Gender::Male));
+    assert!(OutdoorEvent::Track4x400mMixedRelay.exists_in_edition(Edition::Wa2022, &Gender::Female));
+
+    assert!(!OutdoorEvent::Track110mHurdles.exists_in_edition(Edition::Wa2022, &Gender::Female));
+}
+
+#[test]
+fn test_preformance_kind() {
+    assert_eq!(OutdoorEvent::Track100m.preformance(10.5), Preformance::Time(std::time::Duration::from_millis(10500)));
+    assert_eq!(OutdoorEvent::HighJump.preformance(2.01), Preformance::Distance(2.01));
+    assert_eq!(OutdoorEvent::Decathlon.preformance(8124.0), Preformance::Points(8124));
+
+    assert_eq!(OutdoorEvent::PoleVault.measurement_kind(), MeasurementKind::Distance);
+    assert_eq!(OutdoorEvent::Track4x400mMixedRelay.measurement_kind(), MeasurementKind::Time);
+
+    assert_eq!(OutdoorEvent::LongJump.parse_preformance("7.85"), Ok(Preformance::Distance(7.85)));
+    assert_eq!(OutdoorEvent::Decathlon.parse_preformance("8124"), Ok(Preformance::Points(8124)));
+}
+
+#[test]
+fn test_format_preformance() {
+    let marathon = OutdoorEvent::Marathon.parse_preformance("2:08:34.12").unwrap();
+    assert_eq!(OutdoorEvent::Marathon.format_preformance(marathon), "2:08:35");
+
+    let steeple = OutdoorEvent::Track3kmSteeplechase.parse_preformance("8:05.123").unwrap();
+    assert_eq!(OutdoorEvent::Track3kmSteeplechase.format_preformance(steeple), "8:05.13");
+}
+
+#[test]
+fn test_parse_codename() {
+    assert_eq!("3000m SC".parse(), Ok(OutdoorEvent::Track3kmSteeplechase));
+    assert_eq!("3000 SC".parse::<OutdoorEvent>(), Err(IaafScoringError::UnknownEvent("3000 SC".to_string())));
+}
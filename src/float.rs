@@ -0,0 +1,36 @@
+/// The float functions `std` provides, for `no_std` builds where they come from `libm`. With `std`
+/// the inherent methods are used instead, so the results don't change between the builds
+pub(crate) trait Float {
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn powi(self, n: i32) -> Self;
+}
+
+impl Float for f64 {
+    fn floor(self) -> Self {
+        libm::floor(self)
+    }
+
+    fn ceil(self) -> Self {
+        libm::ceil(self)
+    }
+
+    fn round(self) -> Self {
+        libm::round(self)
+    }
+
+    fn sqrt(self) -> Self {
+        libm::sqrt(self)
+    }
+
+    fn powf(self, n: Self) -> Self {
+        libm::pow(self, n)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        libm::pow(self, n as f64)
+    }
+}
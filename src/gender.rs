@@ -1,6 +1,7 @@
 use crate::Codename;
+use strum::EnumIter;
 
-#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, EnumIter, Debug)]
 pub enum Gender {
     Male,
     Female,
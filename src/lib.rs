@@ -1,11 +1,26 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod age_grading;
+pub mod combined;
+pub mod equivalence;
 pub mod error;
 pub mod event;
+#[cfg(not(feature = "std"))]
+mod float;
 pub mod gender;
 pub mod methods;
 pub mod preformance;
+#[cfg(feature = "std")]
+pub mod rankings;
+pub mod wind;
 
 #[cfg(test)]
 mod tests;
+#[cfg(all(test, not(feature = "std")))]
+compile_error!("the tests need the `std` feature");
 trait Codename {
     fn to_codename(&self) -> &str;
     fn from_codename(code: &str) -> Option<Self> where Self: Sized;
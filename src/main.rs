@@ -1,4 +1,3 @@
-use iaaf_scoring;
 use iaaf_scoring::methods::ScoringMethod;
 use iaaf_scoring::preformance::Preformance;
 
@@ -8,17 +7,23 @@ fn main() {
     let mut table = iaaf_scoring::methods::iaaf_2017_tables_outdoor::Iaaf2017Tables::new();
     //table.load_all_tables();
 
-    let points = table
-        .score(
-            iaaf_scoring::event::OutdoorEvent::HighJump,
-            iaaf_scoring::gender::Gender::Female,
-            Preformance::Distance(1.64),
-        )
-        .unwrap();
+    let score = match table.score(
+        iaaf_scoring::event::OutdoorEvent::HighJump,
+        iaaf_scoring::gender::Gender::Female,
+        Preformance::Distance(1.64),
+    ) {
+        Ok(score) => score,
+        Err(e) => {
+            log::error!("Failed to score: {}", e);
+            return;
+        }
+    };
 
     log::info!(
-        "Points for {} HJ FEMALE: {}",
+        "Points for {} HJ FEMALE: {} ({:?} {:?})",
         Preformance::Distance(1.64),
-        points
+        score.points,
+        score.position,
+        score.row
     );
 }
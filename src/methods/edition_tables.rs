@@ -0,0 +1,146 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use crate::error::IaafScoringError;
+use crate::event::TableEvent;
+use crate::gender::Gender;
+use crate::methods::formula::CurveCoefficients;
+use crate::methods::{check_preformance_kind, lookup_performance, lookup_points, CachedTable, Edition, EmbeddedTables, LookupPolicy, LookupResult, Score, ScoringMethod};
+use crate::preformance::Preformance;
+
+/// An edition of the tables as a type, so every edition has its own scoring method type
+pub trait TablesEdition {
+    const EDITION: Edition;
+}
+
+/// The IAAF 2017 edition, see `Edition::Iaaf2017`
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Iaaf2017;
+
+impl TablesEdition for Iaaf2017 {
+    const EDITION: Edition = Edition::Iaaf2017;
+}
+
+/// The WA 2022 edition, see `Edition::Wa2022`
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Wa2022;
+
+impl TablesEdition for Wa2022 {
+    const EDITION: Edition = Edition::Wa2022;
+}
+
+/// Scores outdoor and indoor events with the tables of an edition
+pub struct EditionTables<Ed> {
+    policy: LookupPolicy,
+    extrapolate: bool,
+    edition: PhantomData<Ed>,
+}
+
+impl<Ed> Default for EditionTables<Ed> {
+    fn default() -> Self {
+        Self {
+            policy: LookupPolicy::default(),
+            extrapolate: false,
+            edition: PhantomData,
+        }
+    }
+}
+
+impl<Ed: TablesEdition, E: TableEvent> ScoringMethod<E> for EditionTables<Ed> {
+    fn edition_name(&self) -> &'static str {
+        Ed::EDITION.name()
+    }
+
+    fn lookup(&mut self, event: E, gender: Gender, performance: Preformance) -> Result<LookupResult, IaafScoringError> {
+        let name = Self::table_for(event, gender)?;
+        check_preformance_kind(event.codename(), event.measurement_kind(), performance)?;
+        let performance = event.to_fully_automatic(performance);
+
+        EmbeddedTables::read_table(&name, |table| {
+            lookup_points(table, event.compare_type(), performance, self.policy, || self.curve(table))
+        })
+    }
+
+    fn performance_for_points(&mut self, event: E, gender: Gender, points: u64) -> Result<Preformance, IaafScoringError> {
+        let name = Self::table_for(event, gender)?;
+
+        EmbeddedTables::read_table(&name, |table| {
+            lookup_performance(table, points).map(|perf| event.preformance(perf))
+        })?
+        .ok_or_else(|| IaafScoringError::PointsOutOfRange {
+            event: event.codename().to_string(),
+            points,
+        })
+    }
+
+    /// Scores the performances while holding the tables once, instead of once per performance
+    fn score_batch(&mut self, results: &[(E, Gender, Preformance)]) -> Vec<Result<Score<E>, IaafScoringError>> {
+        EmbeddedTables::read_tables(
+            results,
+            |&(event, gender, performance)| {
+                // in the same order as `lookup`, so every result fails like it does there
+                Self::check_table(event, gender)?;
+                check_preformance_kind(event.codename(), event.measurement_kind(), performance)?;
+                Ok((event, gender))
+            },
+            |(event, gender)| Self::table_for(event, gender),
+            |&(event, gender, performance), table| {
+                let fully_automatic = event.to_fully_automatic(performance);
+                let curve = || self.curve(table);
+                let result = lookup_points(table, event.compare_type(), fully_automatic, self.policy, curve);
+                Score::from_lookup(result, Ed::EDITION.name(), event, gender, performance)
+            },
+        )
+    }
+}
+
+impl<Ed: TablesEdition> EditionTables<Ed> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how performances that fall between two rows of a table are scored
+    pub fn with_policy(mut self, policy: LookupPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets whether performances off the table are scored by continuing the scoring curve, instead
+    /// of getting the points of the best row or 0 points
+    pub fn with_extrapolation(mut self, extrapolate: bool) -> Self {
+        self.extrapolate = extrapolate;
+        self
+    }
+
+    /// Returns the curve to extrapolate off the table with, if extrapolation is enabled
+    fn curve(&self, table: &CachedTable) -> Option<CurveCoefficients> {
+        if self.extrapolate {
+            table.curve()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the name of the table for the event, if this edition has one
+    fn table_for<E: TableEvent>(event: E, gender: Gender) -> Result<String, IaafScoringError> {
+        Self::check_table(event, gender)?;
+        Ok(Ed::EDITION.table_name(E::LOCATION, gender, event.codename()))
+    }
+
+    /// Checks that this edition has a table for the event, without naming it
+    fn check_table<E: TableEvent>(event: E, gender: Gender) -> Result<(), IaafScoringError> {
+        if !event.exists_for_gender(&gender) {
+            return Err(IaafScoringError::GenderNotEligible {
+                event: event.codename().to_string(),
+                gender,
+            });
+        }
+
+        if !event.exists_in_edition(Ed::EDITION, &gender) {
+            let name = Ed::EDITION.table_name(E::LOCATION, gender, event.codename());
+            return Err(IaafScoringError::TableMissing(name));
+        }
+
+        Ok(())
+    }
+}
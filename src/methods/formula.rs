@@ -0,0 +1,316 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::ToString;
+use crate::error::IaafScoringError;
+use crate::event::{EventCompareType, MeasurementKind, OutdoorEvent};
+use crate::gender::Gender;
+use crate::methods::{check_preformance_kind, Edition, EmbeddedTables, LookupOutcome, LookupResult, Rows, ScoringMethod, TablePosition};
+use crate::preformance::Preformance;
+use crate::Codename;
+use log::trace;
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use strum::IntoEnumIterator;
+
+/// The coefficients of the curve the scoring tables are generated from:
+/// `points = a·(x + b)² + c`, with `x` the performance in seconds, meters or points
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CurveCoefficients {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+impl CurveCoefficients {
+    /// Returns the (fractional) points for the performance. Past the vertex of the curve, where
+    /// worse performances would start earning more points again, the curve is kept flat
+    pub fn points(&self, performance: f64, cmp_type: EventCompareType) -> f64 {
+        let vertex = -self.b;
+        let x = match cmp_type {
+            EventCompareType::SmallerIsBetter => performance.min(vertex),
+            EventCompareType::GreaterIsBetter => performance.max(vertex),
+        };
+
+        self.a * (x + self.b).powi(2) + self.c
+    }
+
+    /// Returns the performance that earns exactly the given amount of points, or `None` if the
+    /// curve never reaches that amount with a positive performance
+    pub fn performance_for_points(&self, points: f64, cmp_type: EventCompareType) -> Option<f64> {
+        let squared = (points - self.c) / self.a;
+        if !squared.is_finite() || squared < 0.0 {
+            return None;
+        }
+
+        let performance = match cmp_type {
+            EventCompareType::SmallerIsBetter => -self.b - squared.sqrt(),
+            EventCompareType::GreaterIsBetter => -self.b + squared.sqrt(),
+        };
+
+        // e.g. a time below zero for more points than the curve gives the fastest possible time
+        Some(performance).filter(|performance| performance.is_finite() && *performance > 0.0)
+    }
+
+    /// Fits the curve through the rows of a table with least squares, returns `None` if the table
+    /// has less than three distinct performances
+    pub(super) fn fit(table: &(impl Rows + ?Sized)) -> Option<Self> {
+        if table.row_count() < 3 {
+            return None;
+        }
+
+        // center and scale the performances, to keep the normal equations well conditioned
+        let n = table.row_count() as f64;
+        let mean = table.rows().map(|e| e.performance).sum::<f64>() / n;
+        let scale = table.rows().map(|e| (e.performance - mean).abs()).fold(0.0, f64::max);
+        if scale == 0.0 {
+            return None;
+        }
+
+        // normal equations of points = p2·u² + p1·u + p0, with u the scaled performance
+        let mut matrix = [[0.0; 4]; 3];
+        for entry in table.rows() {
+            let u = (entry.performance - mean) / scale;
+            let powers = [u * u, u, 1.0];
+            for row in 0..3 {
+                for col in 0..3 {
+                    matrix[row][col] += powers[row] * powers[col];
+                }
+                matrix[row][3] += powers[row] * entry.points as f64;
+            }
+        }
+        let [p2, p1, p0] = solve(matrix)?;
+
+        // undo the scaling, then complete the square
+        let alpha = p2 / (scale * scale);
+        let beta = p1 / scale - 2.0 * alpha * mean;
+        let gamma = p2 * mean * mean / (scale * scale) - p1 * mean / scale + p0;
+        if alpha == 0.0 {
+            return None;
+        }
+
+        Some(Self {
+            a: alpha,
+            b: beta / (2.0 * alpha),
+            c: gamma - beta * beta / (4.0 * alpha),
+        })
+    }
+
+    /// Returns the largest difference in points between the curve and the rows of the table
+    fn max_deviation(&self, table: &(impl Rows + ?Sized), cmp_type: EventCompareType) -> f64 {
+        table
+            .rows()
+            .map(|e| (self.points(e.performance, cmp_type) - e.points as f64).abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Solves a 3x3 system of linear equations (given as an augmented matrix) with Gaussian elimination
+fn solve(mut m: [[f64; 4]; 3]) -> Option<[f64; 3]> {
+    for col in 0..3 {
+        let pivot = (col..3).max_by(|a, b| m[*a][col].abs().total_cmp(&m[*b][col].abs()))?;
+        if m[pivot][col].abs() < f64::EPSILON {
+            return None;
+        }
+        m.swap(col, pivot);
+
+        let pivot_row = m[col];
+        for (idx, row) in m.iter_mut().enumerate() {
+            if idx != col {
+                let factor = row[col] / pivot_row[col];
+                for (value, pivot_value) in row.iter_mut().zip(pivot_row.iter()).skip(col) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+    }
+
+    Some([m[0][3] / m[0][0], m[1][3] / m[1][1], m[2][3] / m[2][2]])
+}
+
+/// The curve fitted through a scoring table, with how far it strays from the table
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CurveFit {
+    pub coefficients: CurveCoefficients,
+    /// The largest difference in points between the curve and any row of the table
+    pub max_deviation: f64,
+}
+
+/// Fits the curve through the embedded outdoor table of the edition for the event and gender. Fails
+/// with `CurveFitFailed` if the table has too few distinct rows to fit a curve through
+pub fn fit_table(edition: Edition, event: OutdoorEvent, gender: Gender) -> Result<CurveFit, IaafScoringError> {
+    if !event.exists_for_gender(&gender) {
+        return Err(IaafScoringError::GenderNotEligible {
+            event: event.to_codename().to_string(),
+            gender,
+        });
+    }
+
+    let name = edition.table_name("Outdoor", gender, event.to_codename());
+    EmbeddedTables::read_table(&name, |table| {
+        table.curve().map(|coefficients| CurveFit {
+            coefficients,
+            max_deviation: coefficients.max_deviation(table, event.compare_type()),
+        })
+    })?
+    .ok_or(IaafScoringError::CurveFitFailed(name))
+}
+
+/// Scores outdoor events with the closed-form curves instead of the tables, so performances off
+/// the tables can be scored, and the tables don't have to be kept around
+#[derive(Default)]
+pub struct FormulaScoring {
+    curves: BTreeMap<(OutdoorEvent, Gender), CurveCoefficients>,
+}
+
+impl FormulaScoring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fits the curves through every embedded outdoor table of the edition, events without a table
+    /// are skipped. Fails if a table has no curve, see `fit_table`
+    pub fn from_tables(edition: Edition) -> Result<Self, IaafScoringError> {
+        let mut scoring = Self::new();
+        for event in OutdoorEvent::iter() {
+            for gender in Gender::iter() {
+                if !event.exists_in_edition(edition, &gender) {
+                    continue;
+                }
+
+                match fit_table(edition, event, gender) {
+                    Ok(fit) => {
+                        trace!("Fitted {:?} {:?}, deviation {:.3}", event, gender, fit.max_deviation);
+                        scoring.insert(event, gender, fit.coefficients);
+                    }
+                    Err(IaafScoringError::TableMissing(_)) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        Ok(scoring)
+    }
+
+    pub fn insert(&mut self, event: OutdoorEvent, gender: Gender, coefficients: CurveCoefficients) {
+        self.curves.insert((event, gender), coefficients);
+    }
+
+    pub fn coefficients(&self, event: OutdoorEvent, gender: Gender) -> Option<&CurveCoefficients> {
+        self.curves.get(&(event, gender))
+    }
+
+    fn curve_for(&self, event: OutdoorEvent, gender: Gender) -> Result<&CurveCoefficients, IaafScoringError> {
+        if !event.exists_for_gender(&gender) {
+            return Err(IaafScoringError::GenderNotEligible {
+                event: event.to_codename().to_string(),
+                gender,
+            });
+        }
+
+        self.coefficients(event, gender).ok_or_else(|| {
+            IaafScoringError::TableMissing(format!("{} - {}", gender.to_codename(), event.to_codename()))
+        })
+    }
+}
+
+impl ScoringMethod<OutdoorEvent> for FormulaScoring {
+    fn edition_name(&self) -> &'static str {
+        "Formula"
+    }
+
+    /// The curve is evaluated directly, so every result is reported as exact and on a row
+    fn lookup(
+        &mut self,
+        event: OutdoorEvent,
+        gender: Gender,
+        performance: Preformance,
+    ) -> Result<LookupResult, IaafScoringError> {
+        let curve = self.curve_for(event, gender)?;
+        check_preformance_kind(event.to_codename(), event.measurement_kind(), performance)?;
+        let performance = event.to_fully_automatic(performance);
+
+        let (points, position) = match performance.to_number() {
+            Some(number) => (curve.points(number, event.compare_type()).max(0.0), TablePosition::OnRow),
+            None => (0.0, TablePosition::NoPerformance),
+        };
+
+        Ok(LookupResult {
+            points,
+            outcome: LookupOutcome::Exact,
+            position,
+            row: None,
+        })
+    }
+
+    fn performance_for_points(
+        &mut self,
+        event: OutdoorEvent,
+        gender: Gender,
+        points: u64,
+    ) -> Result<Preformance, IaafScoringError> {
+        let curve = self.curve_for(event, gender)?;
+
+        curve
+            .performance_for_points(points as f64, event.compare_type())
+            .map(|perf| match event.measurement_kind() {
+                // whole points, rounded towards the better performance so it earns the points
+                MeasurementKind::Points => match event.compare_type() {
+                    EventCompareType::GreaterIsBetter => event.preformance(perf.ceil()),
+                    EventCompareType::SmallerIsBetter => event.preformance(perf.floor()),
+                },
+                MeasurementKind::Time | MeasurementKind::Distance => event.preformance(perf),
+            })
+            .ok_or_else(|| IaafScoringError::PointsOutOfRange {
+                event: event.to_codename().to_string(),
+                points,
+            })
+    }
+}
+
+#[test]
+fn test_fit_curve() {
+    // a curve shaped like the men's 100m, rounded down to whole points like the tables are
+    let curve = CurveCoefficients { a: 24.642211664, b: -17.0, c: 0.0 };
+    let table: Vec<super::TableEntry> = (0..700)
+        .map(|i| 9.5 + i as f64 * 0.01)
+        .map(|performance| super::TableEntry {
+            performance,
+            points: curve.points(performance, EventCompareType::SmallerIsBetter).floor() as u64,
+        })
+        .collect();
+
+    let fit = CurveCoefficients::fit(&table[..]).unwrap();
+    assert!((fit.b - curve.b).abs() < 0.01);
+    assert!(fit.max_deviation(&table[..], EventCompareType::SmallerIsBetter) < 1.0);
+
+    let mut scoring = FormulaScoring::new();
+    scoring.insert(OutdoorEvent::Track100m, Gender::Male, curve);
+    let score = scoring.score(OutdoorEvent::Track100m, Gender::Male, Preformance::Time(std::time::Duration::from_millis(10000))).unwrap();
+    assert_eq!(score.points, 1207);
+    assert_eq!(score.edition, "Formula");
+
+    // slower than the vertex of the curve
+    let score = scoring.score(OutdoorEvent::Track100m, Gender::Male, Preformance::Time(std::time::Duration::from_secs(20))).unwrap();
+    assert_eq!(score.points, 0);
+
+    let performance = scoring.performance_for_points(OutdoorEvent::Track100m, Gender::Male, 1207).unwrap();
+    assert!((performance.to_number().unwrap() - 10.00135).abs() < 1e-4);
+
+    // would take a negative time
+    assert_eq!(
+        scoring.performance_for_points(OutdoorEvent::Track100m, Gender::Male, 10000),
+        Err(IaafScoringError::PointsOutOfRange { event: "100m".to_string(), points: 10000 })
+    );
+}
+
+#[test]
+fn test_performance_for_whole_points() {
+    // 1001 points takes 1000.4998... points in the event, which has to round up to earn them
+    let curve = CurveCoefficients { a: 0.001, b: 0.0, c: 0.0 };
+    let mut scoring = FormulaScoring::new();
+    scoring.insert(OutdoorEvent::Decathlon, Gender::Male, curve);
+
+    let performance = scoring.performance_for_points(OutdoorEvent::Decathlon, Gender::Male, 1001).unwrap();
+    assert_eq!(performance, Preformance::Points(1001));
+    assert!(scoring.score(OutdoorEvent::Decathlon, Gender::Male, performance).unwrap().points >= 1001);
+}
@@ -0,0 +1,4 @@
+use crate::methods::edition_tables::{EditionTables, Iaaf2017};
+
+/// The IAAF 2017 scoring tables for indoor events
+pub type Iaaf2017TablesIndoor = EditionTables<Iaaf2017>;
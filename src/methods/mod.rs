@@ -1,119 +1,1142 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::hash::Hash;
+#[cfg(feature = "std")]
 use std::collections::HashMap;
-use std::io::{Cursor, Read};
-use rust_embed::RustEmbed;
-use crate::event::{EventCompareType, OutdoorEvent};
+#[cfg(feature = "runtime-tables")]
+use std::io::Cursor;
+#[cfg(feature = "std")]
+use std::sync::{Mutex, PoisonError, RwLock};
+#[cfg(feature = "std")]
+use std::time::Duration;
+#[cfg(feature = "runtime-tables")]
+use std::time::Instant;
+use crate::event::{EventCompareType, IndoorEvent, MeasurementKind, OutdoorEvent};
+use crate::error::IaafScoringError;
 use crate::gender::Gender;
 use crate::preformance::Preformance;
+#[cfg(feature = "std")]
 use lazy_static::lazy_static;
-use log::{error, info, trace, warn};
+#[cfg(feature = "runtime-tables")]
+use log::error;
+#[cfg(feature = "runtime-tables")]
 use serde::Deserialize;
-use tar::Archive;
 use crate::Codename;
+use strum::EnumIter;
+#[cfg(feature = "std")]
+use strum::IntoEnumIterator;
+use iaaf_2017_tables_indoor::Iaaf2017TablesIndoor;
+use iaaf_2017_tables_outdoor::Iaaf2017Tables;
+use wa_2022_tables::Wa2022Tables;
+use formula::CurveCoefficients;
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+#[cfg(feature = "runtime-tables")]
+use sources::{EmbeddedArchives, TableSource};
 
+pub mod edition_tables;
+pub mod formula;
+pub mod iaaf_2017_tables_indoor;
 pub mod iaaf_2017_tables_outdoor;
+#[cfg(feature = "runtime-tables")]
+pub mod sources;
+#[cfg(feature = "static-tables")]
+mod static_tables;
+pub mod wa_2022_tables;
 
-#[derive(Deserialize)]
+#[cfg_attr(feature = "runtime-tables", derive(Deserialize))]
+#[derive(Clone, Copy)]
 struct TableEntry {
     performance: f64,
     points: u64,
 }
 
+/// The rows of a table as the lookups get them: parsed into the table cache, or straight from the
+/// tables compiled into the crate. Without `std` there is no cache, only compiled tables
+#[cfg(feature = "std")]
+#[derive(Clone, Copy)]
+enum TableRows<'a> {
+    Parsed(&'a [TableEntry]),
+    #[cfg(feature = "static-tables")]
+    Compiled(&'static [(u32, u16)]),
+}
+#[cfg(not(feature = "std"))]
+type TableRows<'a> = &'a [(u32, u16)];
+
+/// A table sorted from the best to the worst performance, however its rows are stored
+trait Rows {
+    /// Returns the row at the index, or `None` past the end of the table
+    fn get_row(&self, idx: usize) -> Option<TableEntry>;
+
+    fn row_count(&self) -> usize;
+
+    fn row(&self, idx: usize) -> TableEntry {
+        self.get_row(idx).expect("row index out of the table")
+    }
+
+    fn rows(&self) -> impl Iterator<Item = TableEntry> + '_ {
+        (0..).map_while(|idx| self.get_row(idx))
+    }
+
+    /// Returns the index of the first row the predicate doesn't hold for, like
+    /// `slice::partition_point` the predicate must hold for all rows before it
+    fn partition_rows(&self, pred: impl Fn(&TableEntry) -> bool) -> usize;
+}
+
+impl Rows for [TableEntry] {
+    fn get_row(&self, idx: usize) -> Option<TableEntry> {
+        self.get(idx).copied()
+    }
+
+    fn row_count(&self) -> usize {
+        self.len()
+    }
+
+    fn partition_rows(&self, pred: impl Fn(&TableEntry) -> bool) -> usize {
+        self.partition_point(pred)
+    }
+}
+
+impl Rows for [(u32, u16)] {
+    fn get_row(&self, idx: usize) -> Option<TableEntry> {
+        self.get(idx).map(compiled_entry)
+    }
+
+    fn row_count(&self) -> usize {
+        self.len()
+    }
+
+    fn partition_rows(&self, pred: impl Fn(&TableEntry) -> bool) -> usize {
+        self.partition_point(|row| pred(&compiled_entry(row)))
+    }
+}
+
+#[cfg(feature = "std")]
+impl Rows for TableRows<'_> {
+    fn get_row(&self, idx: usize) -> Option<TableEntry> {
+        match self {
+            TableRows::Parsed(rows) => rows.get_row(idx),
+            #[cfg(feature = "static-tables")]
+            TableRows::Compiled(rows) => rows.get_row(idx),
+        }
+    }
+
+    fn row_count(&self) -> usize {
+        match self {
+            TableRows::Parsed(rows) => rows.len(),
+            #[cfg(feature = "static-tables")]
+            TableRows::Compiled(rows) => rows.len(),
+        }
+    }
+
+    fn partition_rows(&self, pred: impl Fn(&TableEntry) -> bool) -> usize {
+        match self {
+            TableRows::Parsed(rows) => rows.partition_rows(pred),
+            #[cfg(feature = "static-tables")]
+            TableRows::Compiled(rows) => rows.partition_rows(pred),
+        }
+    }
+}
+
+/// Converts a row of the tables compiled into the crate, which has the performance in thousandths
+fn compiled_entry(&(performance, points): &(u32, u16)) -> TableEntry {
+    TableEntry {
+        performance: performance as f64 / 1000.0,
+        points: points as u64,
+    }
+}
+
+/// A table as the lookups get it, with the curve fitted through its rows. With `std` the curve is
+/// only fitted the first time it's needed, and kept in `EmbeddedTables` per table name
+struct CachedTable<'a> {
+    rows: TableRows<'a>,
+    #[cfg(feature = "std")]
+    name: &'a str,
+    #[cfg(feature = "std")]
+    curves: &'a Mutex<HashMap<String, Option<CurveCoefficients>>>,
+}
+
+impl CachedTable<'_> {
+    /// Returns the curve fitted through the table, or `None` if no curve could be fitted
+    #[cfg(feature = "std")]
+    fn curve(&self) -> Option<CurveCoefficients> {
+        let mut curves = self.curves.lock().unwrap_or_else(PoisonError::into_inner);
+        *curves
+            .entry(self.name.to_string())
+            .or_insert_with(|| CurveCoefficients::fit(self))
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn curve(&self) -> Option<CurveCoefficients> {
+        CurveCoefficients::fit(self)
+    }
+}
+
+impl Rows for CachedTable<'_> {
+    fn get_row(&self, idx: usize) -> Option<TableEntry> {
+        self.rows.get_row(idx)
+    }
+
+    fn row_count(&self) -> usize {
+        self.rows.row_count()
+    }
+
+    fn partition_rows(&self, pred: impl Fn(&TableEntry) -> bool) -> usize {
+        self.rows.partition_rows(pred)
+    }
+}
+
+/// How a performance that falls between two rows of a table is scored
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Default, Debug)]
+pub enum LookupPolicy {
+    /// The points of the next worse row, as the official rules prescribe
+    #[default]
+    Official,
+    /// Interpolates linearly between the two rows, giving a fractional amount of points
+    Linear,
+    /// The points of the row closest to the performance
+    Nearest,
+}
+
+/// How the points of a performance were found in the table
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum LookupOutcome {
+    /// The performance matches a row of the table (or there is no performance to score)
+    Exact,
+    /// The performance falls between two rows, and got the points of the next worse row
+    /// (`LookupPolicy::Official`)
+    Floored,
+    /// The performance falls between two rows, and got the points of the closest row
+    /// (`LookupPolicy::Nearest`)
+    Rounded,
+    /// The performance falls between two rows, and got the points interpolated between them
+    /// (`LookupPolicy::Linear`)
+    Interpolated,
+    /// The performance falls off the table, and got the points of the best row or 0 points
+    Clamped,
+    /// The performance falls off the table, and was scored by continuing the scoring curve
+    Extrapolated,
+    /// The performance was converted to the equivalent of another event before it was looked up,
+    /// with a conversion that isn't part of the tables (see `Implement::score`), so the points are
+    /// an estimate and not official
+    Estimated,
+}
+
+/// Where a performance falls relative to the rows of the table
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum TablePosition {
+    /// The performance matches a row of the table
+    OnRow,
+    /// The performance falls between two rows of the table
+    BetweenRows,
+    /// The performance is better than the best row of the table
+    OffTableHigh,
+    /// The performance is worse than the worst row of the table
+    OffTableLow,
+    /// There is no performance to look up (DNF, NM, etc.)
+    NoPerformance,
+}
+
+/// A row of a scoring table
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TableRow {
+    pub performance: Preformance,
+    pub points: u64,
+}
+
+/// The (fractional) points of a performance, with how they were found in the table
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LookupResult {
+    pub points: f64,
+    pub outcome: LookupOutcome,
+    pub position: TablePosition,
+    /// The row the points were taken from, or the closest row for performances off the table.
+    /// When interpolating linearly this is the next worse row
+    pub row: Option<TableRow>,
+}
+
+/// The score of a performance, with everything needed to trace it back to a row of a table
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Score<E> {
+    /// The points, rounded down to whole points
+    pub points: u64,
+    /// The points according to the lookup policy, only fractional when interpolating linearly
+    pub fractional_points: f64,
+    /// The row the points were taken from, see `LookupResult::row`
+    pub row: Option<TableRow>,
+    /// The name of the edition of the tables, e.g. `IAAF 2017`
+    pub edition: &'static str,
+    pub event: E,
+    pub gender: Gender,
+    pub performance: Preformance,
+    pub outcome: LookupOutcome,
+    pub position: TablePosition,
+}
+
+impl<E> Score<E> {
+    /// Builds the score from the result of looking the performance up in a table of the edition
+    pub fn from_lookup(
+        result: LookupResult,
+        edition: &'static str,
+        event: E,
+        gender: Gender,
+        performance: Preformance,
+    ) -> Self {
+        Self {
+            points: result.points.floor() as u64,
+            fractional_points: result.points,
+            row: result.row,
+            edition,
+            event,
+            gender,
+            performance,
+            outcome: result.outcome,
+            position: result.position,
+        }
+    }
+
+    pub fn is_off_table_high(&self) -> bool {
+        self.position == TablePosition::OffTableHigh
+    }
+
+    pub fn is_off_table_low(&self) -> bool {
+        self.position == TablePosition::OffTableLow
+    }
+
+    pub fn is_between_rows(&self) -> bool {
+        self.position == TablePosition::BetweenRows
+    }
+}
+
 pub trait ScoringMethod<E> {
-    /// returns the score that corresponds with the performance
+    /// returns the name of the edition of the tables the scores are looked up in
+    fn edition_name(&self) -> &'static str;
+
+    /// returns the score that corresponds with the performance, rounded down to whole points, with
+    /// the row of the table it was found in
     fn score(
         &mut self,
         event: E,
         gender: Gender,
         performance: Preformance,
-    ) -> Option<u64>;
+    ) -> Result<Score<E>, IaafScoringError>
+    where
+        E: Copy,
+    {
+        let result = self.lookup(event, gender, performance)?;
+
+        Ok(Score::from_lookup(result, self.edition_name(), event, gender, performance))
+    }
+
+    /// returns the scores of many performances at once, in the same order. Each performance is
+    /// scored like `score` does, failing on its own without affecting the others
+    fn score_batch(&mut self, results: &[(E, Gender, Preformance)]) -> Vec<Result<Score<E>, IaafScoringError>>
+    where
+        E: Copy,
+    {
+        results
+            .iter()
+            .map(|&(event, gender, performance)| self.score(event, gender, performance))
+            .collect()
+    }
+
+    /// returns the score that corresponds with the performance according to the lookup policy of
+    /// the scoring method, which is only fractional when interpolating between table rows
+    fn score_fractional(
+        &mut self,
+        event: E,
+        gender: Gender,
+        performance: Preformance,
+    ) -> Result<f64, IaafScoringError> {
+        self.lookup(event, gender, performance).map(|result| result.points)
+    }
+
+    /// returns the fractional score of the performance, together with whether it matched a row of
+    /// the table exactly, fell between two rows, or fell off the table
+    fn lookup(
+        &mut self,
+        event: E,
+        gender: Gender,
+        performance: Preformance,
+    ) -> Result<LookupResult, IaafScoringError>;
+
+    /// returns the weakest performance that still earns at least the given amount of points
+    fn performance_for_points(
+        &mut self,
+        event: E,
+        gender: Gender,
+        points: u64,
+    ) -> Result<Preformance, IaafScoringError>;
+}
+
+/// The published editions of the scoring tables
+#[derive(Clone, Copy, Eq, PartialEq, Hash, EnumIter, Debug)]
+pub enum Edition {
+    /// IAAF Scoring Tables of Athletics, 2017 revised edition
+    Iaaf2017,
+    /// World Athletics Scoring Tables of Athletics, 2022 edition
+    Wa2022,
 }
 
-#[derive(RustEmbed)]
-#[folder = "resources/"]
-#[include = "*.tar.lzma"]
-#[exclude = "*.csv"]
-struct EmbeddedLZMATables;
+impl Edition {
+    /// Returns the short name of the edition, e.g. `IAAF 2017`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Edition::Iaaf2017 => "IAAF 2017",
+            Edition::Wa2022 => "WA 2022",
+        }
+    }
+
+    pub fn year(&self) -> u16 {
+        match self {
+            Edition::Iaaf2017 => 2017,
+            Edition::Wa2022 => 2022,
+        }
+    }
+
+    /// Returns the outdoor scoring method for this edition
+    pub fn outdoor(&self) -> Box<dyn ScoringMethod<OutdoorEvent>> {
+        self.outdoor_with_policy(LookupPolicy::Official)
+    }
+
+    /// Returns the outdoor scoring method for this edition, with the given lookup policy
+    pub fn outdoor_with_policy(&self, policy: LookupPolicy) -> Box<dyn ScoringMethod<OutdoorEvent>> {
+        match self {
+            Edition::Iaaf2017 => Box::new(Iaaf2017Tables::new().with_policy(policy)),
+            Edition::Wa2022 => Box::new(Wa2022Tables::new().with_policy(policy)),
+        }
+    }
+
+    /// Returns the indoor scoring method for this edition
+    pub fn indoor(&self) -> Box<dyn ScoringMethod<IndoorEvent>> {
+        self.indoor_with_policy(LookupPolicy::Official)
+    }
+
+    /// Returns the indoor scoring method for this edition, with the given lookup policy
+    pub fn indoor_with_policy(&self, policy: LookupPolicy) -> Box<dyn ScoringMethod<IndoorEvent>> {
+        match self {
+            Edition::Iaaf2017 => Box::new(Iaaf2017TablesIndoor::new().with_policy(policy)),
+            Edition::Wa2022 => Box::new(Wa2022Tables::new().with_policy(policy)),
+        }
+    }
+
+    /// Formats the name of the table file, as generated by `extractor.py`
+    fn table_name(&self, location: &str, gender: Gender, event: &str) -> String {
+        format!(
+            "Table {} {} - {} - {}.csv",
+            location,
+            self.year(),
+            gender.to_codename(),
+            event
+        )
+    }
+}
 
+#[cfg(feature = "std")]
 lazy_static! {
-    pub static ref TABLES: EmbeddedTables = EmbeddedTables::init();
+    pub static ref TABLES: RwLock<EmbeddedTables> = RwLock::new(EmbeddedTables::default());
 }
 
+/// How long it took to make a table available for scoring
+#[cfg(feature = "std")]
+#[derive(Clone, PartialEq, Debug)]
+pub struct TableLoad {
+    pub table: String,
+    /// The time spent decompressing the archive the table is in, zero if it was decompressed for
+    /// an earlier table or the table was read from another source
+    pub decompression: Duration,
+    pub parsing: Duration,
+}
+
+/// The scoring tables, read from the embedded archives and any other table sources that were loaded.
+///
+/// Embedded archives are only decompressed once a table from their edition is needed, and every
+/// table is only parsed the first time it's used. With the `static-tables` feature, tables compiled
+/// into the crate are looked up in place, before any archive
+#[cfg(feature = "std")]
+#[derive(Default)]
 pub struct EmbeddedTables {
     tables: HashMap<String, Vec<TableEntry>>,
+    /// Decompressed tables that haven't been parsed yet
+    #[cfg(feature = "runtime-tables")]
+    unparsed: HashMap<String, Vec<u8>>,
+    /// The embedded archives that were decompressed, with the error if that failed
+    #[cfg(feature = "runtime-tables")]
+    unpacked: HashMap<String, Result<(), IaafScoringError>>,
+    loads: Vec<TableLoad>,
+    /// The curves fitted through the tables for extrapolation, by table name
+    curves: Mutex<HashMap<String, Option<CurveCoefficients>>>,
 }
 
+#[cfg(feature = "std")]
 impl EmbeddedTables {
-    fn init() -> Self {
-        let mut tables = HashMap::new();
-        trace!("Listing embedded LZMA tables");
-        for i in EmbeddedLZMATables::iter() {
-            trace!("- {}", i);
-
-            let file = EmbeddedLZMATables::get(&i).unwrap();
-            let mut buf = Vec::with_capacity(file.data.len());
-            lzma_rs::lzma_decompress(&mut Cursor::new(file.data), &mut buf).unwrap();
-
-            let mut archive = Archive::new(Cursor::new(buf));
-            for entry in archive.entries().unwrap() {
-                let mut file = entry.unwrap();
-                let mut buf = Vec::new();
-                file.read_to_end(&mut buf).unwrap();
-
-                let path = file.path().unwrap().file_name().unwrap().to_string_lossy().to_string();
-                trace!("Reading {}", path);
-                let event = path.split("-").last().unwrap().replace(".csv", "");
-                let cmp_type = {
-                    if path.to_lowercase().contains("indoor") {
-                        warn!("Unimplemented indoor events");
-                        continue;
-                    } else if path.to_lowercase().contains("outdoor") {
-                        match OutdoorEvent::from_codename(event.trim()) {
-                            Some(e) => e.compare_type(),
-                            None => {
-                                error!("Invalid outdoor event {}", event.trim());
-                                continue;
-                            }
-                        }
-                    } else {
-                        error!("Neither indoor nor outdoor table: {}", path);
-                        continue;
+    /// Loads the outdoor tables of the edition for the events (for both genders) ahead of time, so
+    /// the first scores don't have to wait for them. Returns how many tables were loaded
+    pub fn prewarm_outdoor(edition: Edition, events: &[OutdoorEvent]) -> Result<usize, IaafScoringError> {
+        let names = events.iter().flat_map(|event| {
+            Gender::iter()
+                .filter(|gender| event.exists_in_edition(edition, gender))
+                .map(|gender| edition.table_name("Outdoor", gender, event.to_codename()))
+        });
+
+        Self::prewarm(names)
+    }
+
+    /// Loads the indoor tables of the edition for the events (for both genders) ahead of time, see
+    /// `prewarm_outdoor`
+    pub fn prewarm_indoor(edition: Edition, events: &[IndoorEvent]) -> Result<usize, IaafScoringError> {
+        let names = events.iter().flat_map(|event| {
+            Gender::iter()
+                .filter(|gender| event.exists_for_gender(gender))
+                .map(|gender| edition.table_name("Indoor", gender, event.to_codename()))
+        });
+
+        Self::prewarm(names)
+    }
+
+    fn prewarm(names: impl Iterator<Item = String>) -> Result<usize, IaafScoringError> {
+        let mut tables = TABLES.write().unwrap_or_else(PoisonError::into_inner);
+        let mut count = 0;
+        for name in names {
+            match tables.load_table(&name) {
+                Ok(()) => count += 1,
+                Err(IaafScoringError::TableMissing(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Returns how long every table that was loaded so far took to load, in the order they were
+    /// loaded
+    pub fn load_times() -> Vec<TableLoad> {
+        TABLES.read().unwrap_or_else(PoisonError::into_inner).loads.clone()
+    }
+
+    /// Makes sure the table is available, from the compiled tables or the embedded archives
+    fn load_table(&mut self, table: &str) -> Result<(), IaafScoringError> {
+        if self.cached(table).is_some() {
+            return Ok(());
+        }
+
+        self.load_unparsed(table)
+    }
+
+    #[cfg(feature = "runtime-tables")]
+    fn insert(&mut self, table: &str, decompression: Duration, parsing: Duration, entries: Vec<TableEntry>) {
+        self.loads.push(TableLoad {
+            table: table.to_string(),
+            decompression,
+            parsing,
+        });
+        self.tables.insert(table.to_string(), entries);
+        // a table read again from another source needs its curve fitted again
+        self.curves.get_mut().unwrap_or_else(PoisonError::into_inner).remove(table);
+    }
+
+    /// Returns the table if it was read from a source or is compiled into the crate. Tables read
+    /// from a source replace the compiled ones
+    fn cached<'a>(&'a self, name: &'a str) -> Option<CachedTable<'a>> {
+        let rows = match self.tables.get(name) {
+            Some(rows) => TableRows::Parsed(rows),
+            #[cfg(feature = "static-tables")]
+            None => TableRows::Compiled(static_tables::table(name)?),
+            #[cfg(not(feature = "static-tables"))]
+            None => return None,
+        };
+
+        Some(CachedTable {
+            rows,
+            name,
+            curves: &self.curves,
+        })
+    }
+
+    #[cfg(not(feature = "runtime-tables"))]
+    fn load_unparsed(&mut self, table: &str) -> Result<(), IaafScoringError> {
+        Err(IaafScoringError::TableMissing(table.to_string()))
+    }
+
+    /// Runs the function for every item with its table. Items are grouped by `key` (e.g. the event
+    /// and gender), so every table is only named and loaded once, and the read lock is only taken
+    /// once for the whole batch
+    fn read_tables<I, K: Copy + Eq + Hash, T>(
+        items: &[I],
+        key: impl Fn(&I) -> Result<K, IaafScoringError>,
+        table_for: impl Fn(K) -> Result<String, IaafScoringError>,
+        func: impl Fn(&I, &CachedTable) -> T,
+    ) -> Vec<Result<T, IaafScoringError>> {
+        let keys = items.iter().map(key).collect::<Vec<_>>();
+        let mut names = HashMap::new();
+        for &key in keys.iter().flatten() {
+            names.entry(key).or_insert_with(|| table_for(key));
+        }
+
+        let loaded = {
+            let tables = TABLES.read().unwrap_or_else(PoisonError::into_inner);
+            names.values().flatten().all(|name| tables.cached(name).is_some())
+        };
+        if !loaded {
+            let mut tables = TABLES.write().unwrap_or_else(PoisonError::into_inner);
+            for name in names.values_mut() {
+                if let Ok(table) = name {
+                    if let Err(e) = tables.load_table(table) {
+                        *name = Err(e);
                     }
-                };
+                }
+            }
+        }
+
+        let tables = TABLES.read().unwrap_or_else(PoisonError::into_inner);
+        let resolved = names
+            .iter()
+            .map(|(key, name)| {
+                let table = name.as_ref().map_err(Clone::clone).and_then(|name| {
+                    tables.cached(name).ok_or_else(|| IaafScoringError::TableMissing(name.clone()))
+                });
+                (key, table)
+            })
+            .collect::<HashMap<_, _>>();
+
+        items
+            .iter()
+            .zip(keys)
+            .map(|(item, key)| {
+                let table = resolved[&key?].as_ref().map_err(Clone::clone)?;
+                Ok(func(item, table))
+            })
+            .collect()
+    }
+
+    fn read_table<T, F: Fn(&CachedTable) -> T>(table: &str, func: F) -> Result<T, IaafScoringError> {
+        if let Some(table) = TABLES.read().unwrap_or_else(PoisonError::into_inner).cached(table) {
+            return Ok(func(&table));
+        }
 
-                let table = parse_table(buf, cmp_type);
+        let mut tables = TABLES.write().unwrap_or_else(PoisonError::into_inner);
+        tables.load_table(table)?;
+        tables
+            .cached(table)
+            .map(|table| func(&table))
+            .ok_or_else(|| IaafScoringError::TableMissing(table.to_string()))
+    }
+}
+
+/// Without `std` there is no cache to load tables into, the tables compiled into the crate with the
+/// `static-tables` feature are looked up directly
+#[cfg(not(feature = "std"))]
+pub struct EmbeddedTables;
+
+#[cfg(not(feature = "std"))]
+impl EmbeddedTables {
+    fn table(table: &str) -> Result<CachedTable<'static>, IaafScoringError> {
+        #[cfg(feature = "static-tables")]
+        if let Some(rows) = static_tables::table(table) {
+            return Ok(CachedTable { rows });
+        }
 
-                tables.insert(path, table);
+        Err(IaafScoringError::TableMissing(table.to_string()))
+    }
+
+    /// Runs the function for every item with its table, see the `std` version
+    fn read_tables<I, K: Copy + Eq + Hash, T>(
+        items: &[I],
+        key: impl Fn(&I) -> Result<K, IaafScoringError>,
+        table_for: impl Fn(K) -> Result<String, IaafScoringError>,
+        func: impl Fn(&I, &CachedTable) -> T,
+    ) -> Vec<Result<T, IaafScoringError>> {
+        items
+            .iter()
+            .map(|item| {
+                let table = Self::table(&table_for(key(item)?)?)?;
+                Ok(func(item, &table))
+            })
+            .collect()
+    }
+
+    fn read_table<T, F: Fn(&CachedTable) -> T>(table: &str, func: F) -> Result<T, IaafScoringError> {
+        Self::table(table).map(|table| func(&table))
+    }
+}
+
+#[cfg(feature = "runtime-tables")]
+impl EmbeddedTables {
+    /// Reads every table of the source into this instance instead of the tables the scoring methods
+    /// use (see `load_source`), e.g. to check a source or measure how long it takes to read.
+    /// Returns how many tables were read
+    pub fn load(&mut self, source: &dyn TableSource) -> Result<usize, IaafScoringError> {
+        let mut count = 0;
+        for (path, buf) in source.files()? {
+            let cmp_type = match table_compare_type(&path) {
+                Some(cmp_type) => cmp_type,
+                None => continue,
+            };
+
+            let start = Instant::now();
+            let table = parse_table(&path, buf, cmp_type)?;
+            self.insert(&path, Duration::ZERO, start.elapsed(), table);
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Reads every table of the source, replacing tables with the same name that were read before,
+    /// so corrected tables can be loaded without rebuilding the crate. Returns how many tables were
+    /// read
+    pub fn load_source(source: &dyn TableSource) -> Result<usize, IaafScoringError> {
+        TABLES.write().unwrap_or_else(PoisonError::into_inner).load(source)
+    }
+
+    /// Parses the table, decompressing the embedded archive of its edition if needed
+    fn load_unparsed(&mut self, table: &str) -> Result<(), IaafScoringError> {
+        let start = Instant::now();
+        if !self.unparsed.contains_key(table) {
+            self.unpack_embedded(table)?;
+        }
+        let decompression = start.elapsed();
+
+        let missing = || IaafScoringError::TableMissing(table.to_string());
+        let data = self.unparsed.remove(table).ok_or_else(missing)?;
+        let cmp_type = table_compare_type(table).ok_or_else(missing)?;
+
+        let start = Instant::now();
+        let parsed = parse_table(table, data, cmp_type)?;
+        self.insert(table, decompression, start.elapsed(), parsed);
+
+        Ok(())
+    }
+
+    /// Decompresses the embedded archives for the year of the table, if they weren't already
+    fn unpack_embedded(&mut self, table: &str) -> Result<(), IaafScoringError> {
+        // tables are named like "Table Outdoor 2017 - MALE - 100m.csv"
+        let year = match table.split_whitespace().nth(2) {
+            Some(year) => year,
+            None => return Ok(()),
+        };
+
+        for archive in EmbeddedArchives::names().filter(|archive| archive.contains(year)) {
+            if let Some(result) = self.unpacked.get(&archive) {
+                result.clone()?;
+                continue;
             }
+
+            let result = EmbeddedArchives::unpack(&archive).map(|files| {
+                for (name, data) in files {
+                    self.unparsed.entry(name).or_insert(data);
+                }
+            });
+            self.unpacked.insert(archive, result.clone());
+            result?;
         }
 
-        Self {
-            tables
+        Ok(())
+    }
+}
+
+/// Returns how the performances in the table are compared, from the event in its name (in the
+/// naming scheme of `extractor.py`), or `None` if the name doesn't belong to a known table
+#[cfg(feature = "runtime-tables")]
+fn table_compare_type(path: &str) -> Option<EventCompareType> {
+    let event = path.rsplit('-').next()?.replace(".csv", "");
+
+    if path.to_lowercase().contains("indoor") {
+        match IndoorEvent::from_codename(event.trim()) {
+            Some(e) => Some(e.compare_type()),
+            None => {
+                error!("Invalid indoor event {}", event.trim());
+                None
+            }
         }
+    } else if path.to_lowercase().contains("outdoor") {
+        match OutdoorEvent::from_codename(event.trim()) {
+            Some(e) => Some(e.compare_type()),
+            None => {
+                error!("Invalid outdoor event {}", event.trim());
+                None
+            }
+        }
+    } else {
+        error!("Neither indoor nor outdoor table: {}", path);
+        None
     }
+}
 
-    fn read_table<T, F: Fn(&Vec<TableEntry>) -> T>(table: &str, func: F) -> Option<T> {
-        TABLES.tables.get(table).map(|v| func(v))
+/// Checks that the preformance is measured the way the event is, so a distance can't be scored in a
+/// sprint for example
+fn check_preformance_kind(
+    event: &str,
+    expected: MeasurementKind,
+    performance: Preformance,
+) -> Result<(), IaafScoringError> {
+    // there is nothing to measure if the athlete didn't produce a preformance
+    if performance.kind().is_some_and(|kind| kind != expected) {
+        return Err(IaafScoringError::WrongPreformanceKind {
+            event: event.to_string(),
+            expected,
+            preformance: performance,
+        });
     }
+
+    Ok(())
 }
 
-fn parse_table(data: Vec<u8>, k: EventCompareType) -> Vec<TableEntry> {
-    let mut data: Vec<TableEntry> = csv::ReaderBuilder::new()
+/// Returns the points for the performance in the table (sorted from best to worst) according to the
+/// lookup policy, or 0 if there is none. Performances off the table get the points of the best entry
+/// or 0 points, unless `curve` returns a curve: then the scoring curve fitted through the table is
+/// continued past its ends, without going below 0 points, below the best entry for better
+/// performances or above the worst entry for worse ones. `curve` is only called for performances
+/// off the table
+fn lookup_points(
+    table: &(impl Rows + ?Sized),
+    cmp_type: EventCompareType,
+    performance: Preformance,
+    policy: LookupPolicy,
+    curve: impl FnOnce() -> Option<CurveCoefficients>,
+) -> LookupResult {
+    let (kind, performance) = match (performance.kind(), performance.to_number()) {
+        (Some(kind), Some(performance)) => (kind, performance),
+        // DNF, NM, etc. don't earn any points
+        _ => {
+            return LookupResult {
+                points: 0.0,
+                outcome: LookupOutcome::Exact,
+                position: TablePosition::NoPerformance,
+                row: None,
+            }
+        }
+    };
+    let row = |entry: &TableEntry| {
+        Some(TableRow {
+            performance: Preformance::from_number(kind, entry.performance),
+            points: entry.points,
+        })
+    };
+
+    // the table is sorted best first, so the rows the performance reached are all at the end
+    let idx = match cmp_type {
+        EventCompareType::SmallerIsBetter => table.partition_rows(|perf| perf.performance < performance),
+        EventCompareType::GreaterIsBetter => table.partition_rows(|perf| perf.performance > performance),
+    };
+
+    if let Some(perf) = table.get_row(idx) {
+        if perf.performance == performance {
+            return LookupResult {
+                points: perf.points as f64,
+                outcome: LookupOutcome::Exact,
+                position: TablePosition::OnRow,
+                row: row(&perf),
+            };
+        }
+
+        // the performance is better than the best entry, and never scores less than it
+        if idx == 0 {
+            let points = extrapolate_points(curve(), cmp_type, performance).map(|points| points.max(perf.points as f64));
+            return LookupResult {
+                points: points.unwrap_or(perf.points as f64),
+                outcome: points.map_or(LookupOutcome::Clamped, |_| LookupOutcome::Extrapolated),
+                position: TablePosition::OffTableHigh,
+                row: row(&perf),
+            };
+        }
+
+        let better = table.row(idx - 1);
+        let matched = match policy {
+            LookupPolicy::Official | LookupPolicy::Linear => perf,
+            LookupPolicy::Nearest => {
+                if (better.performance - performance).abs() < (perf.performance - performance).abs() {
+                    better
+                } else {
+                    perf
+                }
+            }
+        };
+        let (points, outcome) = match policy {
+            LookupPolicy::Linear => {
+                let fraction = (perf.performance - performance) / (perf.performance - better.performance);
+                let points = perf.points as f64 + fraction * (better.points as f64 - perf.points as f64);
+                (points, LookupOutcome::Interpolated)
+            }
+            LookupPolicy::Official => (matched.points as f64, LookupOutcome::Floored),
+            LookupPolicy::Nearest => (matched.points as f64, LookupOutcome::Rounded),
+        };
+        return LookupResult {
+            points,
+            outcome,
+            position: TablePosition::BetweenRows,
+            row: row(&matched),
+        };
+    }
+
+    // Falls outside of the table, and never scores more than the worst entry
+    let worst = table.row_count().checked_sub(1).map(|idx| table.row(idx));
+    let points = extrapolate_points(curve(), cmp_type, performance)
+        .map(|points| worst.map_or(points, |worst| points.min(worst.points as f64)));
+    LookupResult {
+        points: points.unwrap_or(0.0),
+        outcome: points.map_or(LookupOutcome::Clamped, |_| LookupOutcome::Extrapolated),
+        position: TablePosition::OffTableLow,
+        row: worst.and_then(|worst| row(&worst)),
+    }
+}
+
+/// Scores a performance off the table with the curve fitted through it, or returns `None` if there
+/// is no curve (extrapolation is disabled or no curve could be fitted). The points never go below 0
+fn extrapolate_points(curve: Option<CurveCoefficients>, cmp_type: EventCompareType, performance: f64) -> Option<f64> {
+    curve.map(|curve| curve.points(performance, cmp_type).max(0.0))
+}
+
+/// Returns the weakest performance in the table (sorted from best to worst) that still earns at
+/// least the given amount of points, or `None` if no entry in the table earns that many points
+fn lookup_performance(table: &(impl Rows + ?Sized), points: u64) -> Option<f64> {
+    // the points only go down further down the table
+    let idx = table.partition_rows(|perf| perf.points >= points);
+    idx.checked_sub(1).map(|idx| table.row(idx).performance)
+}
+
+#[cfg(feature = "runtime-tables")]
+fn parse_table(name: &str, data: Vec<u8>, k: EventCompareType) -> Result<Vec<TableEntry>, IaafScoringError> {
+    let mut data = csv::ReaderBuilder::new()
         .delimiter(b',')
         .has_headers(true)
         .from_reader(Cursor::new(data))
         .records()
-        .filter(|i| i.is_ok())
-        .map(|i| i.unwrap())
-        .map(|i| TableEntry {
-            performance: i[0].parse().unwrap(),
-            points: i[1].parse().unwrap(),
+        .map(|i| {
+            let malformed = |line: Option<u64>, reason: String| IaafScoringError::MalformedRow {
+                table: name.to_string(),
+                line: line.unwrap_or_default(),
+                reason,
+            };
+
+            let i = i.map_err(|e| malformed(e.position().map(|p| p.line()), e.to_string()))?;
+            let line = i.position().map(|p| p.line());
+            let field = |idx: usize| {
+                i.get(idx).ok_or_else(|| malformed(line, format!("missing column {}", idx)))
+            };
+
+            Ok(TableEntry {
+                performance: field(0)?.parse().map_err(|e| malformed(line, format!("{}", e)))?,
+                points: field(1)?.parse().map_err(|e| malformed(line, format!("{}", e)))?,
+            })
         })
-        .collect();
+        .collect::<Result<Vec<TableEntry>, IaafScoringError>>()?;
 
     match k {
         EventCompareType::SmallerIsBetter => data.sort_by(|a, b| a.performance.total_cmp(&b.performance)),
         EventCompareType::GreaterIsBetter => data.sort_by(|a, b| b.performance.total_cmp(&a.performance))
     };
 
-    data
-}
\ No newline at end of file
+    Ok(data)
+}
+
+#[test]
+fn test_compiled_rows() {
+    let rows: &[(u32, u16)] = &[(10_000, 1100), (10_010, 1098), (10_020, 1098), (10_030, 1095)];
+
+    assert_eq!(rows.get_row(1).map(|row| (row.performance, row.points)), Some((10.01, 1098)));
+    assert!(rows.get_row(4).is_none());
+    assert_eq!(rows.rows().count(), 4);
+    assert_eq!(rows.partition_rows(|row| row.performance < 10.015), 2);
+    assert_eq!(rows.partition_rows(|row| row.points >= 1098), 3);
+}
+
+#[test]
+fn test_lookup_performance() {
+    let table = [
+        TableEntry { performance: 10.0, points: 1100 },
+        TableEntry { performance: 10.01, points: 1098 },
+        TableEntry { performance: 10.02, points: 1098 },
+        TableEntry { performance: 10.03, points: 1095 },
+    ];
+
+    assert_eq!(lookup_performance(&table[..], 1098), Some(10.02));
+    assert_eq!(lookup_performance(&table[..], 1097), Some(10.02));
+    assert_eq!(lookup_performance(&table[..], 1100), Some(10.0));
+    assert_eq!(lookup_performance(&table[..], 0), Some(10.03));
+    assert_eq!(lookup_performance(&table[..], 1101), None);
+}
+
+#[test]
+fn test_lookup_points() {
+    let table = [
+        TableEntry { performance: 2.01, points: 1100 },
+        TableEntry { performance: 2.0, points: 1090 },
+        TableEntry { performance: 1.99, points: 1080 },
+    ];
+
+    let lookup = |performance, policy| lookup_points(&table[..], EventCompareType::GreaterIsBetter, performance, policy, || None).points;
+
+    assert_eq!(lookup(Preformance::Distance(2.005), LookupPolicy::Official), 1090.0);
+    assert_eq!(lookup(Preformance::Distance(1.5), LookupPolicy::Official), 0.0);
+    assert_eq!(lookup(Preformance::Distance(2.5), LookupPolicy::Official), 1100.0);
+    assert_eq!(lookup(Preformance::NoResult(crate::preformance::NonPreformance::Nh), LookupPolicy::Official), 0.0);
+
+    assert!((lookup(Preformance::Distance(2.0075), LookupPolicy::Linear) - 1097.5).abs() < 1e-9);
+    assert_eq!(lookup(Preformance::Distance(2.0), LookupPolicy::Linear), 1090.0);
+    assert_eq!(lookup(Preformance::Distance(2.5), LookupPolicy::Linear), 1100.0);
+    assert_eq!(lookup(Preformance::Distance(1.5), LookupPolicy::Linear), 0.0);
+
+    assert_eq!(lookup(Preformance::Distance(2.0075), LookupPolicy::Nearest), 1100.0);
+    assert_eq!(lookup(Preformance::Distance(2.0025), LookupPolicy::Nearest), 1090.0);
+
+    let outcome = |performance| lookup_points(&table[..], EventCompareType::GreaterIsBetter, performance, LookupPolicy::Official, || None).outcome;
+    assert_eq!(outcome(Preformance::Distance(2.0)), LookupOutcome::Exact);
+    assert_eq!(outcome(Preformance::Distance(2.01)), LookupOutcome::Exact);
+    assert_eq!(outcome(Preformance::Distance(2.005)), LookupOutcome::Floored);
+    assert_eq!(outcome(Preformance::Distance(2.5)), LookupOutcome::Clamped);
+    assert_eq!(outcome(Preformance::Distance(1.5)), LookupOutcome::Clamped);
+}
+
+#[test]
+fn test_extrapolate_points() {
+    // a table like the men's 100m, from 10.00 s to 12.00 s
+    let curve = CurveCoefficients { a: 24.642211664, b: -17.0, c: 0.0 };
+    let table: Vec<TableEntry> = (0..=200)
+        .map(|i| 10.0 + i as f64 * 0.01)
+        .map(|performance| TableEntry {
+            performance,
+            points: curve.points(performance, EventCompareType::SmallerIsBetter).floor() as u64,
+        })
+        .collect();
+
+    let lookup = |seconds: f64| {
+        let performance = Preformance::Time(std::time::Duration::from_secs_f64(seconds));
+        lookup_points(&table[..], EventCompareType::SmallerIsBetter, performance, LookupPolicy::Official, || CurveCoefficients::fit(&table[..]))
+    };
+
+    // faster than the table continues up the curve
+    let result = lookup(9.5);
+    assert_eq!(result.outcome, LookupOutcome::Extrapolated);
+    assert!((result.points - curve.points(9.5, EventCompareType::SmallerIsBetter)).abs() < 2.0);
+    assert!(result.points > table[0].points as f64);
+
+    // slower than the table continues down the curve, but never below 0 points
+    let result = lookup(15.0);
+    assert_eq!(result.outcome, LookupOutcome::Extrapolated);
+    assert!((result.points - curve.points(15.0, EventCompareType::SmallerIsBetter)).abs() < 2.0);
+    assert!(lookup(25.0).points >= 0.0);
+
+    // performances on the table aren't affected
+    assert_eq!(lookup(10.0).outcome, LookupOutcome::Exact);
+    assert_eq!(lookup(10.005).outcome, LookupOutcome::Floored);
+}
+
+#[test]
+fn test_extrapolate_points_bounded_by_table() {
+    let table = [
+        TableEntry { performance: 10.0, points: 1100 },
+        TableEntry { performance: 10.01, points: 1098 },
+        TableEntry { performance: 10.02, points: 1095 },
+    ];
+    let lookup = |seconds: f64, c: f64| {
+        let performance = Preformance::Time(std::time::Duration::from_secs_f64(seconds));
+        let curve = CurveCoefficients { a: 24.642211664, b: -17.0, c };
+        lookup_points(&table[..], EventCompareType::SmallerIsBetter, performance, LookupPolicy::Official, || Some(curve)).points
+    };
+
+    // a curve below the table at its best end still gives the points of the best row
+    assert_eq!(lookup(9.99, -500.0), 1100.0);
+    assert!(lookup(9.9, 0.0) > 1100.0);
+    // a curve above the table at its worst end gives no more than the worst row
+    assert_eq!(lookup(10.03, 500.0), 1095.0);
+    assert!(lookup(10.5, 0.0) < 1095.0);
+}
+
+#[test]
+fn test_lookup_row() {
+    let table = [
+        TableEntry { performance: 10.0, points: 1100 },
+        TableEntry { performance: 10.01, points: 1098 },
+        TableEntry { performance: 10.02, points: 1095 },
+    ];
+    let lookup = |millis, policy| {
+        let performance = Preformance::Time(std::time::Duration::from_millis(millis));
+        lookup_points(&table[..], EventCompareType::SmallerIsBetter, performance, policy, || None)
+    };
+    let row = |millis, points| Some(TableRow { performance: Preformance::Time(std::time::Duration::from_millis(millis)), points });
+
+    let result = lookup(10010, LookupPolicy::Official);
+    assert_eq!((result.position, result.row), (TablePosition::OnRow, row(10010, 1098)));
+
+    let result = lookup(10012, LookupPolicy::Official);
+    assert_eq!((result.position, result.row), (TablePosition::BetweenRows, row(10020, 1095)));
+    let result = lookup(10012, LookupPolicy::Nearest);
+    assert_eq!((result.position, result.row), (TablePosition::BetweenRows, row(10010, 1098)));
+
+    let result = lookup(9900, LookupPolicy::Official);
+    assert_eq!((result.position, result.row), (TablePosition::OffTableHigh, row(10000, 1100)));
+    let result = lookup(10500, LookupPolicy::Official);
+    assert_eq!((result.position, result.row), (TablePosition::OffTableLow, row(10020, 1095)));
+
+    let result = lookup_points(&table[..], EventCompareType::SmallerIsBetter, Preformance::NoResult(crate::preformance::NonPreformance::Dnf), LookupPolicy::Official, || None);
+    assert_eq!((result.position, result.row), (TablePosition::NoPerformance, None));
+}
+
+#[cfg(feature = "runtime-tables")]
+#[test]
+fn test_curve_cache() {
+    let name = "Table Outdoor 2017 - MALE - 100m.csv";
+    let table = |offset: f64| -> Vec<TableEntry> {
+        let curve = CurveCoefficients { a: 24.642211664, b: -17.0 + offset, c: 0.0 };
+        (0..=200)
+            .map(|i| 10.0 + i as f64 * 0.01)
+            .map(|performance| TableEntry {
+                performance,
+                points: curve.points(performance, EventCompareType::SmallerIsBetter).floor() as u64,
+            })
+            .collect()
+    };
+    let fitted = |tables: &EmbeddedTables| tables.curves.lock().unwrap().get(name).copied().flatten();
+
+    let mut tables = EmbeddedTables::default();
+    tables.insert(name, Duration::ZERO, Duration::ZERO, table(0.0));
+    assert_eq!(fitted(&tables), None);
+
+    // the curve is fitted on the first use, and kept for the next ones
+    let curve = tables.cached(name).unwrap().curve();
+    assert!(curve.is_some());
+    assert_eq!(fitted(&tables), curve);
+
+    // reading the table again drops the curve of the old rows
+    tables.insert(name, Duration::ZERO, Duration::ZERO, table(0.5));
+    assert_eq!(fitted(&tables), None);
+    assert_ne!(tables.cached(name).unwrap().curve(), curve);
+}
+
+#[cfg(feature = "runtime-tables")]
+#[test]
+fn test_parse_table() {
+    let table = parse_table("test.csv", b"performance,points\n10.01,1098\n10.0,1100\n".to_vec(), EventCompareType::SmallerIsBetter).unwrap();
+    assert_eq!(table[0].performance, 10.0);
+    assert_eq!(table[1].points, 1098);
+
+    let error = parse_table("test.csv", b"performance,points\n10.0,1100\n10.01,abc\n".to_vec(), EventCompareType::SmallerIsBetter);
+    assert!(matches!(error, Err(IaafScoringError::MalformedRow { line: 3, .. })));
+}
+
+#[cfg(feature = "runtime-tables")]
+#[test]
+fn test_lazy_tables() {
+    let mut tables = EmbeddedTables::default();
+    let name = "Table Outdoor 2017 - MALE - 400m.csv";
+    tables.unparsed.insert(name.to_string(), b"performance,points\n45.0,1100\n".to_vec());
+    tables.unparsed.insert("Table Outdoor 2017 - MALE - 800m.csv".to_string(), b"broken".to_vec());
+
+    // only the requested table is parsed
+    tables.load_table(name).unwrap();
+    assert_eq!(tables.tables[name][0].points, 1100);
+    assert_eq!(tables.loads.len(), 1);
+    assert_eq!(tables.loads[0].table, name);
+
+    // and only once
+    tables.load_table(name).unwrap();
+    assert_eq!(tables.loads.len(), 1);
+
+    assert!(matches!(
+        tables.load_table("Table Outdoor 1999 - MALE - 100m.csv"),
+        Err(IaafScoringError::TableMissing(_))
+    ));
+}
@@ -0,0 +1,188 @@
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::PathBuf;
+use rust_embed::RustEmbed;
+use crate::error::IaafScoringError;
+use log::trace;
+use tar::Archive;
+
+/// Somewhere scoring tables can be read from. Every file is a CSV table named like `extractor.py`
+/// names them, e.g. `Table Outdoor 2017 - MALE - 100m.csv`
+pub trait TableSource {
+    /// Returns the name and contents of every table in the source
+    fn files(&self) -> Result<Vec<(String, Vec<u8>)>, IaafScoringError>;
+}
+
+#[derive(RustEmbed)]
+#[folder = "resources/"]
+#[include = "*.tar.lzma"]
+#[exclude = "*.csv"]
+#[exclude = "wma*"]
+struct EmbeddedLZMATables;
+
+/// The LZMA compressed archives embedded in the crate at compile time
+pub struct EmbeddedArchives;
+
+impl EmbeddedArchives {
+    /// Returns the names of the embedded archives, e.g. `iaaf2017.tar.lzma`
+    pub fn names() -> impl Iterator<Item = String> {
+        EmbeddedLZMATables::iter().map(|name| name.to_string())
+    }
+
+    /// Decompresses one of the embedded archives, returns the name and contents of every table in it
+    pub fn unpack(name: &str) -> Result<Vec<(String, Vec<u8>)>, IaafScoringError> {
+        trace!("Decompressing embedded {}", name);
+        let file = EmbeddedLZMATables::get(name)
+            .ok_or_else(|| IaafScoringError::CorruptArchive(format!("{} is not embedded", name)))?;
+
+        unpack_lzma_archive(name, &file.data)
+    }
+}
+
+impl TableSource for EmbeddedArchives {
+    fn files(&self) -> Result<Vec<(String, Vec<u8>)>, IaafScoringError> {
+        let mut files = Vec::new();
+        trace!("Listing embedded LZMA tables");
+        for i in Self::names() {
+            files.extend(Self::unpack(&i)?);
+        }
+
+        Ok(files)
+    }
+}
+
+/// A directory with CSV tables, like the ones `extractor.py` generates. Other files are ignored
+pub struct CsvDirectory {
+    path: PathBuf,
+}
+
+impl CsvDirectory {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl TableSource for CsvDirectory {
+    fn files(&self) -> Result<Vec<(String, Vec<u8>)>, IaafScoringError> {
+        let unreadable = |e: std::io::Error| IaafScoringError::SourceUnreadable {
+            path: self.path.display().to_string(),
+            reason: e.to_string(),
+        };
+
+        let mut files = Vec::new();
+        trace!("Listing tables in {}", self.path.display());
+        for entry in fs::read_dir(&self.path).map_err(unreadable)? {
+            let path = entry.map_err(unreadable)?.path();
+            if path.extension().is_none_or(|extension| extension != "csv") {
+                continue;
+            }
+
+            if let Some(name) = path.file_name() {
+                trace!("- {}", name.to_string_lossy());
+                files.push((name.to_string_lossy().to_string(), fs::read(&path).map_err(unreadable)?));
+            }
+        }
+
+        Ok(files)
+    }
+}
+
+/// A `.tar.lzma` or `.tar.xz` archive with CSV tables, like the ones `compress_tables.sh` creates
+pub struct ArchiveFile {
+    path: PathBuf,
+}
+
+impl ArchiveFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl TableSource for ArchiveFile {
+    fn files(&self) -> Result<Vec<(String, Vec<u8>)>, IaafScoringError> {
+        let name = self.path.display().to_string();
+        let data = fs::read(&self.path).map_err(|e| IaafScoringError::SourceUnreadable {
+            path: name.clone(),
+            reason: e.to_string(),
+        })?;
+
+        if name.ends_with(".xz") {
+            unpack_xz_archive(&name, &data)
+        } else {
+            unpack_lzma_archive(&name, &data)
+        }
+    }
+}
+
+/// Decompresses an LZMA compressed tar archive, returns the name and contents of every file in it
+pub(crate) fn unpack_lzma_archive(name: &str, data: &[u8]) -> Result<Vec<(String, Vec<u8>)>, IaafScoringError> {
+    let mut buf = Vec::with_capacity(data.len());
+    lzma_rs::lzma_decompress(&mut Cursor::new(data), &mut buf)
+        .map_err(|e| IaafScoringError::CorruptArchive(format!("{}: {}", name, e)))?;
+
+    unpack_tar(name, buf)
+}
+
+/// Decompresses an XZ compressed tar archive, returns the name and contents of every file in it
+pub(crate) fn unpack_xz_archive(name: &str, data: &[u8]) -> Result<Vec<(String, Vec<u8>)>, IaafScoringError> {
+    let mut buf = Vec::with_capacity(data.len());
+    lzma_rs::xz_decompress(&mut Cursor::new(data), &mut buf)
+        .map_err(|e| IaafScoringError::CorruptArchive(format!("{}: {}", name, e)))?;
+
+    unpack_tar(name, buf)
+}
+
+fn unpack_tar(name: &str, buf: Vec<u8>) -> Result<Vec<(String, Vec<u8>)>, IaafScoringError> {
+    let corrupt = |e: std::io::Error| IaafScoringError::CorruptArchive(format!("{}: {}", name, e));
+    let mut archive = Archive::new(Cursor::new(buf));
+    let mut files = Vec::new();
+    for entry in archive.entries().map_err(corrupt)? {
+        let mut file = entry.map_err(corrupt)?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).map_err(corrupt)?;
+
+        if let Some(path) = file.path().map_err(corrupt)?.file_name() {
+            trace!("Reading {}", path.to_string_lossy());
+            files.push((path.to_string_lossy().to_string(), buf));
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+fn tar(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (name, contents) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, contents.as_bytes()).unwrap();
+    }
+
+    builder.into_inner().unwrap()
+}
+
+#[test]
+fn test_archive_file() {
+    let tables = crate::tests::TableDir::new("archive");
+    let dir = tables.path();
+    let data = tar(&[("tables/Table Outdoor 2017 - MALE - 100m.csv", "performance,points\n10.0,1100\n")]);
+
+    let mut xz = Vec::new();
+    lzma_rs::xz_compress(&mut std::io::Cursor::new(&data), &mut xz).unwrap();
+    fs::write(dir.join("tables.tar.xz"), xz).unwrap();
+    let files = ArchiveFile::new(dir.join("tables.tar.xz")).files().unwrap();
+    assert_eq!(files, vec![("Table Outdoor 2017 - MALE - 100m.csv".to_string(), b"performance,points\n10.0,1100\n".to_vec())]);
+
+    let mut lzma = Vec::new();
+    lzma_rs::lzma_compress(&mut std::io::Cursor::new(&data), &mut lzma).unwrap();
+    fs::write(dir.join("tables.tar.lzma"), lzma).unwrap();
+    assert_eq!(ArchiveFile::new(dir.join("tables.tar.lzma")).files().unwrap(), files);
+
+    assert!(matches!(ArchiveFile::new(dir.join("missing.tar.xz")).files(), Err(IaafScoringError::SourceUnreadable { .. })));
+    assert!(matches!(CsvDirectory::new(dir.join("missing")).files(), Err(IaafScoringError::SourceUnreadable { .. })));
+    fs::write(dir.join("corrupt.tar.xz"), b"not an archive").unwrap();
+    assert!(matches!(ArchiveFile::new(dir.join("corrupt.tar.xz")).files(), Err(IaafScoringError::CorruptArchive(_))));
+}
@@ -0,0 +1,10 @@
+// generated by build.rs from the embedded archives
+include!(concat!(env!("OUT_DIR"), "/static_tables.rs"));
+
+/// Returns the rows of the compiled table, performances in thousandths
+pub(super) fn table(name: &str) -> Option<&'static [(u32, u16)]> {
+    TABLES
+        .binary_search_by(|(table, _)| (*table).cmp(name))
+        .ok()
+        .map(|index| TABLES[index].1)
+}
@@ -0,0 +1,4 @@
+use crate::methods::edition_tables::{EditionTables, Wa2022};
+
+/// The World Athletics 2022 scoring tables, for both outdoor and indoor events
+pub type Wa2022Tables = EditionTables<Wa2022>;
@@ -1,38 +1,324 @@
-use std::fmt::{Display, Formatter};
-use std::time::Duration;
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use core::time::Duration;
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use crate::error::IaafScoringError;
+use crate::event::MeasurementKind;
 
-#[derive(PartialEq, Copy, Clone)]
+#[derive(PartialEq, Copy, Clone, Debug)]
 pub enum Preformance {
     /// Preformance is measured in distance (m) (HJ, LJ, etc.)
     Distance(f64),
 
-    /// Preformance is measured in time (s) (100m, 400mH, Marathon, etc.)
+    /// Preformance is measured in time (s) (100m, 400mH, Marathon, etc.), fully automatic timed
     Time(Duration),
 
+    /// Preformance is measured in time (s), but hand timed. It's converted to a fully automatic
+    /// time before it's scored, see `OutdoorEvent::to_fully_automatic`
+    HandTime(Duration),
+
     /// Preformance is measured in points (Decathlon, Heptathlon)
     Points(u16),
+
+    /// There is no preformance to measure (DNF, NM, etc.), this always scores 0 points
+    NoResult(NonPreformance),
+}
+
+/// How a time was measured
+#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub enum TimingMethod {
+    /// Fully automatic timing with a photo finish
+    FullyAutomatic,
+    /// Timed by hand with stopwatches
+    Hand,
+}
+
+/// How precisely times are rounded on result sheets
+#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub enum TimePrecision {
+    /// Rounded up to the next 1/100 s, for races held entirely in the stadium
+    Hundredths,
+    /// Rounded up to the next 1/10 s, for hand timed races
+    Tenths,
+    /// Rounded up to the next whole second, for races held partly or entirely outside the stadium
+    Seconds,
+}
+
+/// The markers used on result sheets when an athlete didn't produce a valid preformance
+#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub enum NonPreformance {
+    /// Did not finish
+    Dnf,
+    /// Did not start
+    Dns,
+    /// Disqualified
+    Dq,
+    /// No valid mark in a throw or horizontal jump
+    Nm,
+    /// No valid height in a vertical jump
+    Nh,
 }
 
 impl Preformance {
-    /// Returns the preformance as a float
+    /// Builds a preformance of the given kind from a number (meters, seconds or points). Times
+    /// that aren't a valid duration, like negative times, become zero instead of panicking
+    pub fn from_number(kind: MeasurementKind, number: f64) -> Self {
+        match kind {
+            MeasurementKind::Distance => Preformance::Distance(number),
+            MeasurementKind::Time => Preformance::Time(Duration::try_from_secs_f64(number).unwrap_or(Duration::ZERO)),
+            MeasurementKind::Points => Preformance::Points(number as u16),
+        }
+    }
+
+    /// Parses a preformance in the notation of official result sheets, for an event measured in
+    /// the given kind:
+    /// - times as `hh:mm:ss.xx`, `mm:ss.xx` or `ss.xx` (the fraction is optional)
+    /// - distances as meters, e.g. `7.85`
+    /// - points as an integer, e.g. `8124`
+    /// - hand times with an `h` suffix, e.g. `10.8h`
+    /// - the markers `DNF`, `DNS`, `DQ`, `NM` and `NH`, regardless of the kind
+    pub fn parse(s: &str, kind: MeasurementKind) -> Result<Self, IaafScoringError> {
+        let s = s.trim();
+        if let Ok(marker) = s.parse::<NonPreformance>() {
+            return Ok(Preformance::NoResult(marker));
+        }
+
+        let invalid = || IaafScoringError::InvalidPreformance(s.to_string());
+        match kind {
+            MeasurementKind::Time => match s.strip_suffix(['h', 'H']) {
+                Some(hand) => parse_time(hand.trim_end()).map(Preformance::HandTime).ok_or_else(invalid),
+                None => parse_time(s).map(Preformance::Time).ok_or_else(invalid),
+            },
+            MeasurementKind::Distance => match s.parse::<f64>() {
+                Ok(distance) if distance.is_finite() && distance >= 0.0 => Ok(Preformance::Distance(distance)),
+                _ => Err(invalid()),
+            },
+            MeasurementKind::Points => s.parse().map(Preformance::Points).map_err(|_| invalid()),
+        }
+    }
+
+    /// Returns how this preformance is measured, or `None` if there is no preformance
+    pub fn kind(&self) -> Option<MeasurementKind> {
+        match self {
+            Preformance::Distance(_) => Some(MeasurementKind::Distance),
+            Preformance::Time(_) | Preformance::HandTime(_) => Some(MeasurementKind::Time),
+            Preformance::Points(_) => Some(MeasurementKind::Points),
+            Preformance::NoResult(_) => None,
+        }
+    }
+
+    /// Returns how the time was measured, or `None` if this isn't a time
+    pub fn timing_method(&self) -> Option<TimingMethod> {
+        match self {
+            Preformance::Time(_) => Some(TimingMethod::FullyAutomatic),
+            Preformance::HandTime(_) => Some(TimingMethod::Hand),
+            _ => None,
+        }
+    }
+
+    /// Formats the preformance the way official result sheets do:
+    /// - times as `2:08:35`, `1:45.32` or `10.45`, rounded up to the given precision
+    /// - hand times as `10.8h`, rounded up to at most tenths
+    /// - distances in meters with two decimals, rounded down to the centimeter
+    /// - points as an integer
+    pub fn to_result_string(&self, precision: TimePrecision) -> String {
+        match self {
+            Preformance::Distance(distance) => {
+                // leave some room for floating point errors, 7.85 might be stored as 7.8499999...
+                let centimeters = (distance * 100.0 + 1e-6).floor() as u64;
+                format!("{}.{:02}", centimeters / 100, centimeters % 100)
+            }
+            Preformance::Time(duration) => format_time(*duration, precision),
+            Preformance::HandTime(duration) => {
+                let precision = match precision {
+                    TimePrecision::Seconds => TimePrecision::Seconds,
+                    TimePrecision::Hundredths | TimePrecision::Tenths => TimePrecision::Tenths,
+                };
+                format!("{}h", format_time(*duration, precision))
+            }
+            Preformance::Points(points) => format!("{}", points),
+            Preformance::NoResult(marker) => format!("{}", marker),
+        }
+    }
+
+    /// Returns the preformance as a float, or `None` if there is no preformance
     /// - For distance this is the distance in meters
     /// - For time this is the time in seconds
     /// - For points this is the amount of points
-    pub fn to_number(&self) -> f64 {
+    pub fn to_number(&self) -> Option<f64> {
         match self {
-            Preformance::Distance(distance) => *distance,
-            Preformance::Time(duration) => duration.as_secs_f64(),
-            Preformance::Points(points) => *points as f64,
+            Preformance::Distance(distance) => Some(*distance),
+            Preformance::Time(duration) | Preformance::HandTime(duration) => Some(duration.as_secs_f64()),
+            Preformance::Points(points) => Some(*points as f64),
+            Preformance::NoResult(_) => None,
         }
     }
 }
 
-impl Display for Preformance {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+/// The longest time that is parsed (in seconds), longer than any race on a result sheet, multi-day
+/// ultras included. Anything longer is a typo
+const MAX_PARSED_TIME: u64 = 1000 * 60 * 60;
+
+/// Parses `hh:mm:ss.xx`, `mm:ss.xx` or `ss.xx`, like `parse_timetamp` in `extractor.py`
+fn parse_time(s: &str) -> Option<Duration> {
+    let mut parts = s.rsplit(':');
+    let seconds = parts.next()?;
+    let minutes = parts.next();
+    let hours = parts.next();
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let (whole, fraction) = match seconds.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (seconds, ""),
+    };
+    if whole.is_empty() || fraction.len() > 9 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let mut secs: u64 = whole.parse().ok()?;
+    let nanos: u32 = format!("{:0<9}", fraction).parse().ok()?;
+
+    // every unit except the leading one has to stay below 60
+    if minutes.is_some() && secs >= 60 {
+        return None;
+    }
+    if let Some(minutes) = minutes {
+        let minutes: u64 = minutes.parse().ok()?;
+        if hours.is_some() && minutes >= 60 {
+            return None;
+        }
+        secs = secs.checked_add(minutes.checked_mul(60)?)?;
+    }
+    if let Some(hours) = hours {
+        let hours: u64 = hours.parse().ok()?;
+        secs = secs.checked_add(hours.checked_mul(60 * 60)?)?;
+    }
+    Some(Duration::new(secs, nanos)).filter(|time| *time <= Duration::from_secs(MAX_PARSED_TIME))
+}
+
+fn format_time(duration: Duration, precision: TimePrecision) -> String {
+    // times are rounded to the microsecond first, so floating point errors can't round them up
+    let micros = (duration.as_nanos() + 500) / 1000;
+    let unit = match precision {
+        TimePrecision::Hundredths => 10_000,
+        TimePrecision::Tenths => 100_000,
+        TimePrecision::Seconds => 1_000_000,
+    };
+    let rounded = micros.div_ceil(unit) * unit;
+
+    let hundredths = (rounded % 1_000_000) / 10_000;
+    let seconds = rounded / 1_000_000;
+    let (hours, minutes, seconds) = (seconds / 3600, (seconds / 60) % 60, seconds % 60);
+
+    let clock = if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}:{:02}", minutes, seconds)
+    } else {
+        format!("{}", seconds)
+    };
+
+    match precision {
+        TimePrecision::Hundredths => format!("{}.{:02}", clock, hundredths),
+        TimePrecision::Tenths => format!("{}.{}", clock, hundredths / 10),
+        TimePrecision::Seconds => clock,
+    }
+}
+
+impl FromStr for NonPreformance {
+    type Err = IaafScoringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "DNF" => Ok(NonPreformance::Dnf),
+            "DNS" => Ok(NonPreformance::Dns),
+            "DQ" | "DSQ" => Ok(NonPreformance::Dq),
+            "NM" => Ok(NonPreformance::Nm),
+            "NH" => Ok(NonPreformance::Nh),
+            _ => Err(IaafScoringError::InvalidPreformance(s.to_string())),
+        }
+    }
+}
+
+impl Display for NonPreformance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
-            Preformance::Distance(dist) => write!(f, "{:.3} m", dist),
-            Preformance::Points(points) => write!(f, "{} pts", points),
-            Preformance::Time(dur) => write!(f, "{:.3} s", dur.as_secs_f64()),
+            NonPreformance::Dnf => write!(f, "DNF"),
+            NonPreformance::Dns => write!(f, "DNS"),
+            NonPreformance::Dq => write!(f, "DQ"),
+            NonPreformance::Nm => write!(f, "NM"),
+            NonPreformance::Nh => write!(f, "NH"),
         }
     }
 }
+
+impl Display for Preformance {
+    /// Formats the preformance like result sheets do, with times in hundredths. Use
+    /// `format_preformance` on the event to round road race times to whole seconds
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.to_result_string(TimePrecision::Hundredths))
+    }
+}
+
+#[test]
+fn test_parse_preformance() {
+    let time = |s| Preformance::parse(s, MeasurementKind::Time);
+
+    assert_eq!(time("10.45"), Ok(Preformance::Time(Duration::from_millis(10450))));
+    assert_eq!(time("1:45.32"), Ok(Preformance::Time(Duration::from_millis(105320))));
+    assert_eq!(time("2:08:35"), Ok(Preformance::Time(Duration::from_secs(7715))));
+    assert_eq!(time("1:02:03.4"), Ok(Preformance::Time(Duration::from_millis(3723400))));
+    assert_eq!(time("dnf"), Ok(Preformance::NoResult(NonPreformance::Dnf)));
+    assert!(time("1:60.00").is_err());
+    assert!(time("1:2:3:4").is_err());
+    assert!(time("10,45").is_err());
+    assert_eq!(time("10.8h"), Ok(Preformance::HandTime(Duration::from_millis(10800))));
+    assert_eq!(time("47.3 h"), Ok(Preformance::HandTime(Duration::from_millis(47300))));
+    assert!(time("h").is_err());
+
+    // too long to be a result, without overflowing on the way
+    assert!(time("9999999999999999:00:00").is_err());
+    assert!(time("99999999999999999:00").is_err());
+    assert!(time("99999999999999999999").is_err());
+    assert_eq!(time("999:59:59"), Ok(Preformance::Time(Duration::from_secs(3599999))));
+    assert!(time("1000:00:00.01").is_err());
+
+    assert_eq!(Preformance::parse("7.85", MeasurementKind::Distance), Ok(Preformance::Distance(7.85)));
+    assert_eq!(Preformance::parse("NM", MeasurementKind::Distance), Ok(Preformance::NoResult(NonPreformance::Nm)));
+    assert!(Preformance::parse("-1", MeasurementKind::Distance).is_err());
+
+    assert_eq!(Preformance::parse("8124", MeasurementKind::Points), Ok(Preformance::Points(8124)));
+    assert!(Preformance::parse("8124.5", MeasurementKind::Points).is_err());
+}
+
+#[test]
+fn test_result_string() {
+    let time = |millis| Preformance::Time(Duration::from_millis(millis));
+
+    assert_eq!(time(10450).to_string(), "10.45");
+    assert_eq!(time(10451).to_string(), "10.46");
+    assert_eq!(Preformance::Time(Duration::from_secs_f64(10.45)).to_string(), "10.45");
+    assert_eq!(time(105320).to_string(), "1:45.32");
+    assert_eq!(time(7715000).to_result_string(TimePrecision::Seconds), "2:08:35");
+    assert_eq!(time(7714100).to_result_string(TimePrecision::Seconds), "2:08:35");
+    assert_eq!(time(3599999).to_string(), "1:00:00.00");
+    assert_eq!(Preformance::HandTime(Duration::from_millis(10800)).to_string(), "10.8h");
+    assert_eq!(Preformance::HandTime(Duration::from_millis(10810)).to_string(), "10.9h");
+
+    assert_eq!(Preformance::Distance(7.85).to_string(), "7.85");
+    assert_eq!(Preformance::Distance(7.859).to_string(), "7.85");
+    assert_eq!(Preformance::Distance(18.0).to_string(), "18.00");
+    assert_eq!(Preformance::Points(8124).to_string(), "8124");
+    assert_eq!(Preformance::NoResult(NonPreformance::Dnf).to_string(), "DNF");
+}
+
+#[test]
+fn test_from_number() {
+    assert_eq!(Preformance::from_number(MeasurementKind::Time, 10.5), Preformance::Time(Duration::from_millis(10500)));
+    assert_eq!(Preformance::from_number(MeasurementKind::Time, -1.0), Preformance::Time(Duration::ZERO));
+    assert_eq!(Preformance::from_number(MeasurementKind::Time, f64::NAN), Preformance::Time(Duration::ZERO));
+}
@@ -0,0 +1,462 @@
+use std::collections::HashMap;
+use crate::error::IaafScoringError;
+use crate::event::OutdoorEvent;
+use crate::gender::Gender;
+use crate::methods::{Score, ScoringMethod};
+use crate::preformance::Preformance;
+use crate::wind::{self, WindAdjustedScore};
+
+/// The categories competitions are assigned to in the World Athletics rankings, from the most to
+/// the least important
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum CompetitionCategory {
+    /// Olympic Games and World Championships
+    OW,
+    /// Diamond League Final
+    DF,
+    /// Other global championships (World Indoor Championships, World Cup, etc.)
+    GW,
+    /// Diamond League meetings
+    GL,
+    /// Area championships and Gold level meetings
+    A,
+    /// Silver level meetings and national championships of large federations
+    B,
+    /// Bronze level meetings and other national championships
+    C,
+    D,
+    E,
+    /// Any other competition under the rules of World Athletics
+    F,
+}
+
+impl CompetitionCategory {
+    fn index(&self) -> usize {
+        match self {
+            CompetitionCategory::OW => 0,
+            CompetitionCategory::DF => 1,
+            CompetitionCategory::GW => 2,
+            CompetitionCategory::GL => 3,
+            CompetitionCategory::A => 4,
+            CompetitionCategory::B => 5,
+            CompetitionCategory::C => 6,
+            CompetitionCategory::D => 7,
+            CompetitionCategory::E => 8,
+            CompetitionCategory::F => 9,
+        }
+    }
+}
+
+/// The round of the competition the place was achieved in
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum Round {
+    Final,
+    SemiFinal,
+    Heat,
+}
+
+/// The event groups that have their own placing score tables
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum EventGroup {
+    /// Sprints, hurdles, middle distance, jumps, throws and relays, contested in up to three rounds
+    Standard,
+    /// 5000m and 10000m, with a larger final and heats only at global championships
+    LongDistance,
+    /// Combined events, road running and race walking, contested in a single race with a large
+    /// field
+    SingleRace,
+}
+
+impl EventGroup {
+    pub fn of(event: OutdoorEvent) -> Self {
+        match event {
+            OutdoorEvent::Track5000m | OutdoorEvent::Track10000m => EventGroup::LongDistance,
+
+            OutdoorEvent::Heptathlon
+            | OutdoorEvent::Decathlon
+            | OutdoorEvent::RoadMile
+            | OutdoorEvent::Road5km
+            | OutdoorEvent::Road10km
+            | OutdoorEvent::Road15km
+            | OutdoorEvent::Road20km
+            | OutdoorEvent::Road25km
+            | OutdoorEvent::Road30km
+            | OutdoorEvent::Road100km
+            | OutdoorEvent::Road10Miles
+            | OutdoorEvent::HalfMarathon
+            | OutdoorEvent::Marathon
+            | OutdoorEvent::Walk3km
+            | OutdoorEvent::Walk5km
+            | OutdoorEvent::Walk10km
+            | OutdoorEvent::Walk15km
+            | OutdoorEvent::Walk20km
+            | OutdoorEvent::Walk30km
+            | OutdoorEvent::Walk35km
+            | OutdoorEvent::Walk50km
+            | OutdoorEvent::Walk3kmTrack
+            | OutdoorEvent::Walk5kmTrack
+            | OutdoorEvent::Walk10kmTrack
+            | OutdoorEvent::Walk15kmTrack
+            | OutdoorEvent::Walk20kmTrack
+            | OutdoorEvent::Walk30kmTrack
+            | OutdoorEvent::Walk35kmTrack
+            | OutdoorEvent::Walk50kmTrack => EventGroup::SingleRace,
+
+            _ => EventGroup::Standard,
+        }
+    }
+
+    /// Returns the placing score table for the round, with a row per category (in the order of
+    /// `CompetitionCategory`) and the overall place the first column belongs to, or `None` if the
+    /// round doesn't earn placing points in this event group
+    fn table(&self, round: Round) -> Option<(&'static [&'static [u64]; 10], u32)> {
+        match (self, round) {
+            (EventGroup::Standard, Round::Final) => Some((&STANDARD_FINAL, 1)),
+            (EventGroup::Standard, Round::SemiFinal) => Some((&STANDARD_SEMI_FINAL, 9)),
+            (EventGroup::Standard, Round::Heat) => Some((&STANDARD_HEAT, 17)),
+            (EventGroup::LongDistance, Round::Final) => Some((&LONG_DISTANCE_FINAL, 1)),
+            (EventGroup::LongDistance, Round::Heat) => Some((&LONG_DISTANCE_HEAT, 13)),
+            (EventGroup::SingleRace, Round::Final) => Some((&SINGLE_RACE_FINAL, 1)),
+            _ => None,
+        }
+    }
+}
+
+// Finalists are placed 1-8
+const STANDARD_FINAL: [&[u64]; 10] = [
+    &[375, 330, 300, 270, 250, 230, 215, 200],
+    &[240, 210, 185, 170, 155, 140, 130, 120],
+    &[200, 170, 150, 140, 130, 120, 110, 100],
+    &[170, 145, 130, 120, 110, 100, 90, 80],
+    &[140, 120, 110, 100, 90, 80, 70, 60],
+    &[100, 80, 70, 60, 55, 50, 45, 40],
+    &[60, 50, 45, 40, 35, 30, 25, 20],
+    &[40, 30, 25, 20, 15, 10, 5],
+    &[25, 20, 15, 10, 5],
+    &[15, 10, 5],
+];
+// Semi-finalists that didn't reach the final are placed 9-16
+const STANDARD_SEMI_FINAL: [&[u64]; 10] = [
+    &[170, 160, 150, 140, 130, 120, 110, 100],
+    &[],
+    &[90, 85, 80, 75, 70, 65, 60, 55],
+    &[],
+    &[50, 45, 40, 35, 30, 25, 20, 15],
+    &[30, 25, 20, 15, 10, 5],
+    &[],
+    &[],
+    &[],
+    &[],
+];
+// Athletes eliminated in the heats are placed 17-24
+const STANDARD_HEAT: [&[u64]; 10] = [
+    &[80, 75, 70, 65, 60, 55, 50, 45],
+    &[],
+    &[45, 40, 35, 30, 25, 20, 15, 10],
+    &[],
+    &[],
+    &[],
+    &[],
+    &[],
+    &[],
+    &[],
+];
+// Finalists are placed 1-12
+const LONG_DISTANCE_FINAL: [&[u64]; 10] = [
+    &[375, 330, 300, 270, 250, 230, 215, 200, 185, 170, 155, 140],
+    &[240, 210, 185, 170, 155, 140, 130, 120, 110, 100, 90, 80],
+    &[200, 170, 150, 140, 130, 120, 110, 100, 90, 80, 70, 60],
+    &[170, 145, 130, 120, 110, 100, 90, 80, 70, 60, 50, 40],
+    &[140, 120, 110, 100, 90, 80, 70, 60, 50, 40, 30, 20],
+    &[100, 80, 70, 60, 55, 50, 45, 40, 35, 30, 25, 20],
+    &[60, 50, 45, 40, 35, 30, 25, 20, 15, 10, 5],
+    &[40, 30, 25, 20, 15, 10, 5],
+    &[25, 20, 15, 10, 5],
+    &[15, 10, 5],
+];
+// Athletes eliminated in the heats are placed 13-20
+const LONG_DISTANCE_HEAT: [&[u64]; 10] = [
+    &[110, 100, 90, 80, 70, 60, 50, 40],
+    &[],
+    &[50, 45, 40, 35, 30, 25, 20, 15],
+    &[],
+    &[],
+    &[],
+    &[],
+    &[],
+    &[],
+    &[],
+];
+// Everyone is placed in a single race, places 1-16
+const SINGLE_RACE_FINAL: [&[u64]; 10] = [
+    &[375, 330, 300, 270, 250, 230, 215, 200, 185, 170, 155, 140, 125, 110, 95, 80],
+    &[240, 210, 185, 170, 155, 140, 130, 120, 110, 100, 90, 80, 70, 60, 50, 40],
+    &[200, 170, 150, 140, 130, 120, 110, 100, 90, 80, 70, 60, 50, 40, 30, 20],
+    &[170, 145, 130, 120, 110, 100, 90, 80, 70, 60, 50, 40, 30, 20, 10],
+    &[140, 120, 110, 100, 90, 80, 70, 60, 50, 40, 30, 20, 10],
+    &[100, 80, 70, 60, 55, 50, 45, 40, 35, 30, 25, 20, 15, 10, 5],
+    &[60, 50, 45, 40, 35, 30, 25, 20, 15, 10, 5],
+    &[40, 30, 25, 20, 15, 10, 5],
+    &[25, 20, 15, 10, 5],
+    &[15, 10, 5],
+];
+
+/// Where an athlete finished in a competition
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct Placing {
+    pub category: CompetitionCategory,
+    /// The last round the athlete competed in
+    pub round: Round,
+    /// The overall place in the competition: finalists are placed 1-8 (1-12 or 1-16 in larger
+    /// finals), athletes eliminated in the semi-finals and heats are placed after them
+    pub place: u32,
+}
+
+/// Returns the placing score for the placing in the event, 0 if the place doesn't earn points
+pub fn placing_score(event: OutdoorEvent, placing: Placing) -> u64 {
+    let (table, first_place) = match EventGroup::of(event).table(placing.round) {
+        Some(table) => table,
+        None => return 0,
+    };
+
+    placing
+        .place
+        .checked_sub(first_place)
+        .and_then(|idx| table[placing.category.index()].get(idx as usize))
+        .copied()
+        .unwrap_or(0)
+}
+
+/// The score of a result in the World Athletics rankings: the result score from the scoring tables
+/// plus the placing score
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PerformanceScore {
+    pub result: Score<OutdoorEvent>,
+    /// The wind modification applied to the points of the result, see `wind::wind_modification`
+    pub wind_modification: i64,
+    pub placing: Placing,
+    pub placing_score: u64,
+    /// The result score plus the wind modification and the placing score
+    pub total: u64,
+}
+
+impl PerformanceScore {
+    /// Adds the placing score to the score of the result
+    pub fn new(result: Score<OutdoorEvent>, placing: Placing) -> Self {
+        Self::with_wind_modification(result, 0, placing)
+    }
+
+    /// Adds the placing score to the wind adjusted score of the result
+    pub fn from_wind_adjusted(result: WindAdjustedScore, placing: Placing) -> Self {
+        Self::with_wind_modification(result.score, result.modification, placing)
+    }
+
+    fn with_wind_modification(result: Score<OutdoorEvent>, wind_modification: i64, placing: Placing) -> Self {
+        let placing_score = placing_score(result.event, placing);
+
+        Self {
+            result,
+            wind_modification,
+            placing,
+            placing_score,
+            total: result.points.saturating_add_signed(wind_modification) + placing_score,
+        }
+    }
+}
+
+/// A calendar date, precise enough to tell whether a result falls in a ranking window
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    pub fn new(year: u16, month: u8, day: u8) -> Self {
+        Self { year, month, day }
+    }
+
+    /// Returns the same day the given amount of months earlier. The day isn't clamped to the
+    /// length of the month, which doesn't matter for comparing dates
+    pub fn months_before(&self, months: u32) -> Self {
+        let total = self.year as i64 * 12 + (self.month as i64 - 1) - months as i64;
+
+        Self {
+            year: total.div_euclid(12) as u16,
+            month: total.rem_euclid(12) as u8 + 1,
+            day: self.day,
+        }
+    }
+}
+
+/// A result of an athlete, as it appears on the result sheet of a competition
+#[derive(Clone, PartialEq, Debug)]
+pub struct DatedResult {
+    pub date: Date,
+    /// Identifies the competition, so only the best results per competition are counted
+    pub competition: String,
+    pub event: OutdoorEvent,
+    pub gender: Gender,
+    pub performance: Preformance,
+    /// The wind reading in m/s, for events where the wind is measured
+    pub wind: Option<f64>,
+    pub placing: Placing,
+}
+
+/// How the results of an athlete are counted towards the ranking score of an event group
+#[derive(Clone, PartialEq, Debug)]
+pub struct RankingRules {
+    /// The main event of the group, followed by the other events that count towards it
+    pub events: Vec<OutdoorEvent>,
+    /// The amount of best results that are averaged
+    pub results_counted: usize,
+    /// The length of the rolling window, 12 or 18 months
+    pub window_months: u32,
+    /// The most results that count from a single competition
+    pub max_per_competition: usize,
+    /// The most results that count from the other events than the main event
+    pub max_other_events: usize,
+}
+
+impl RankingRules {
+    /// The rules for the event group of the main event: 5 results in 12 months, or 3 results in
+    /// 18 months for combined events, road running and race walking. Only one result per
+    /// competition counts
+    pub fn for_event(event: OutdoorEvent) -> Self {
+        let (results_counted, window_months) = match EventGroup::of(event) {
+            EventGroup::Standard | EventGroup::LongDistance => (5, 12),
+            EventGroup::SingleRace => (3, 18),
+        };
+
+        Self {
+            events: vec![event],
+            results_counted,
+            window_months,
+            max_per_competition: 1,
+            max_other_events: 0,
+        }
+    }
+
+    /// Adds an event whose results also count towards the group, for at most `max_other_events`
+    /// results
+    pub fn with_other_event(mut self, event: OutdoorEvent) -> Self {
+        self.events.push(event);
+        self
+    }
+
+    pub fn with_max_other_events(mut self, max_other_events: usize) -> Self {
+        self.max_other_events = max_other_events;
+        self
+    }
+}
+
+/// A result that counted towards the ranking score
+#[derive(Clone, PartialEq, Debug)]
+pub struct CountedResult {
+    pub result: DatedResult,
+    pub score: PerformanceScore,
+}
+
+/// The ranking score of an athlete in an event group
+#[derive(Clone, PartialEq, Debug)]
+pub struct RankingScore {
+    /// The average of the performance scores of the counted results, 0 if none counted
+    pub score: f64,
+    /// The results that counted, from best to worst
+    pub counted: Vec<CountedResult>,
+}
+
+/// Computes the ranking score on the given date: the average of the best performance scores in
+/// the events of the rules, from results in the window that ends on that date.
+///
+/// Fails if a result in the window can't be scored
+pub fn ranking_score<M: ScoringMethod<OutdoorEvent> + ?Sized>(
+    method: &mut M,
+    results: &[DatedResult],
+    rules: &RankingRules,
+    date: Date,
+) -> Result<RankingScore, IaafScoringError> {
+    let window_start = date.months_before(rules.window_months);
+
+    let mut scored = Vec::new();
+    for result in results {
+        if result.date <= window_start || result.date > date || !rules.events.contains(&result.event) {
+            continue;
+        }
+
+        let score = wind::score_with_wind(method, result.event, result.gender, result.performance, result.wind)?;
+        scored.push(CountedResult {
+            result: result.clone(),
+            score: PerformanceScore::from_wind_adjusted(score, result.placing),
+        });
+    }
+    // the most recent result wins a tie
+    scored.sort_by(|a, b| b.score.total.cmp(&a.score.total).then(b.result.date.cmp(&a.result.date)));
+
+    let mut per_competition = HashMap::new();
+    let mut other_events = 0;
+    let mut counted = Vec::new();
+    for result in scored {
+        if counted.len() == rules.results_counted {
+            break;
+        }
+
+        let competition = per_competition.entry(result.result.competition.clone()).or_insert(0);
+        if *competition == rules.max_per_competition {
+            continue;
+        }
+
+        let other_event = rules.events.first() != Some(&result.result.event);
+        if other_event && other_events == rules.max_other_events {
+            continue;
+        }
+
+        *competition += 1;
+        if other_event {
+            other_events += 1;
+        }
+        counted.push(result);
+    }
+
+    let score = match counted.len() {
+        0 => 0.0,
+        n => counted.iter().map(|c| c.score.total as f64).sum::<f64>() / n as f64,
+    };
+
+    Ok(RankingScore { score, counted })
+}
+
+#[test]
+fn test_placing_score() {
+    let placing = |category, round, place| Placing { category, round, place };
+
+    assert_eq!(placing_score(OutdoorEvent::Track100m, placing(CompetitionCategory::OW, Round::Final, 1)), 375);
+    assert_eq!(placing_score(OutdoorEvent::Track100m, placing(CompetitionCategory::OW, Round::Final, 8)), 200);
+    assert_eq!(placing_score(OutdoorEvent::Track100m, placing(CompetitionCategory::OW, Round::Final, 9)), 0);
+    assert_eq!(placing_score(OutdoorEvent::Track100m, placing(CompetitionCategory::OW, Round::SemiFinal, 9)), 170);
+    assert_eq!(placing_score(OutdoorEvent::Track100m, placing(CompetitionCategory::OW, Round::SemiFinal, 3)), 0);
+    assert_eq!(placing_score(OutdoorEvent::Track100m, placing(CompetitionCategory::GW, Round::Heat, 17)), 45);
+    assert_eq!(placing_score(OutdoorEvent::Track100m, placing(CompetitionCategory::GL, Round::SemiFinal, 9)), 0);
+    assert_eq!(placing_score(OutdoorEvent::Track100m, placing(CompetitionCategory::F, Round::Final, 3)), 5);
+    assert_eq!(placing_score(OutdoorEvent::Track100m, placing(CompetitionCategory::F, Round::Final, 4)), 0);
+    assert_eq!(placing_score(OutdoorEvent::Track100m, placing(CompetitionCategory::A, Round::Final, 0)), 0);
+
+    assert_eq!(placing_score(OutdoorEvent::Track10000m, placing(CompetitionCategory::OW, Round::Final, 12)), 140);
+    assert_eq!(placing_score(OutdoorEvent::Track10000m, placing(CompetitionCategory::OW, Round::SemiFinal, 12)), 0);
+    assert_eq!(placing_score(OutdoorEvent::Marathon, placing(CompetitionCategory::OW, Round::Final, 16)), 80);
+    assert_eq!(placing_score(OutdoorEvent::Decathlon, placing(CompetitionCategory::B, Round::Heat, 1)), 0);
+
+    assert_eq!(EventGroup::of(OutdoorEvent::Walk50kmTrack), EventGroup::SingleRace);
+    assert_eq!(placing_score(OutdoorEvent::Walk50kmTrack, placing(CompetitionCategory::OW, Round::Final, 16)), 80);
+    let rules = RankingRules::for_event(OutdoorEvent::Walk50kmTrack);
+    assert_eq!((rules.results_counted, rules.window_months), (3, 18));
+}
+
+#[test]
+fn test_date_window() {
+    assert_eq!(Date::new(2023, 6, 15).months_before(12), Date::new(2022, 6, 15));
+    assert_eq!(Date::new(2023, 3, 1).months_before(18), Date::new(2021, 9, 1));
+    assert_eq!(Date::new(2023, 1, 31).months_before(1), Date::new(2022, 12, 31));
+    assert!(Date::new(2022, 6, 16) > Date::new(2022, 6, 15));
+}
@@ -1,22 +1,422 @@
 use std::time::Duration;
-use crate::event::OutdoorEvent;
+use crate::combined::CombinedEvent;
+use crate::equivalence;
+use crate::error::IaafScoringError;
+use crate::event::{MeasurementKind, OutdoorEvent};
 use crate::gender::Gender;
-use crate::methods::{iaaf_2017_tables_outdoor, ScoringMethod};
-use crate::preformance::Preformance;
+use crate::methods::{formula, iaaf_2017_tables_outdoor, Edition, LookupOutcome, LookupResult, ScoringMethod, TablePosition};
+#[cfg(feature = "runtime-tables")]
+use crate::methods::sources::CsvDirectory;
+#[cfg(feature = "runtime-tables")]
+use crate::methods::EmbeddedTables;
+use crate::preformance::{NonPreformance, Preformance};
+use crate::rankings::{self, CompetitionCategory, DatedResult, Date, PerformanceScore, Placing, RankingRules, Round};
+use crate::wind;
 
+fn time(seconds: f64) -> Preformance {
+    Preformance::Time(Duration::from_secs_f64(seconds))
+}
+
+/// Scores the 100m and 200m on a simple linear scale, so scores can be tested without the embedded
+/// tables
+pub(crate) struct LinearSprints;
+
+impl ScoringMethod<OutdoorEvent> for LinearSprints {
+    fn edition_name(&self) -> &'static str {
+        "Linear"
+    }
+
+    fn lookup(&mut self, event: OutdoorEvent, _gender: Gender, performance: Preformance) -> Result<LookupResult, IaafScoringError> {
+        let points = match event {
+            OutdoorEvent::Track100m => ((20.0 - performance.to_number().unwrap_or(20.0)) * 100.0).round().max(0.0),
+            OutdoorEvent::Track200m => ((40.0 - performance.to_number().unwrap_or(40.0)) * 50.0).round().max(0.0),
+            _ => return Err(IaafScoringError::TableMissing(format!("{:?}", event))),
+        };
+        Ok(LookupResult { points, outcome: LookupOutcome::Exact, position: TablePosition::OnRow, row: None })
+    }
+
+    fn performance_for_points(&mut self, event: OutdoorEvent, _gender: Gender, points: u64) -> Result<Preformance, IaafScoringError> {
+        match event {
+            OutdoorEvent::Track100m => Ok(Preformance::Time(Duration::from_secs_f64(20.0 - points as f64 / 100.0))),
+            OutdoorEvent::Track200m => Ok(Preformance::Time(Duration::from_secs_f64(40.0 - points as f64 / 50.0))),
+            _ => Err(IaafScoringError::TableMissing(format!("{:?}", event))),
+        }
+    }
+}
+
+/// A temporary directory of table files, removed again when it's dropped
+#[cfg(feature = "runtime-tables")]
+pub(crate) struct TableDir {
+    path: std::path::PathBuf,
+}
+
+#[cfg(feature = "runtime-tables")]
+impl TableDir {
+    /// Creates the directory, `name` keeps it apart from the directories of other tests
+    pub(crate) fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("iaaf_scoring_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub(crate) fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    pub(crate) fn write(&self, file: &str, contents: impl AsRef<[u8]>) -> &Self {
+        std::fs::write(self.path.join(file), contents).unwrap();
+        self
+    }
+
+    /// Reads the tables in the directory into the tables the scoring methods use
+    pub(crate) fn load(&self) -> Result<usize, IaafScoringError> {
+        EmbeddedTables::load_source(&CsvDirectory::new(&self.path))
+    }
+}
+
+#[cfg(feature = "runtime-tables")]
+impl Drop for TableDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Needs the tables generated from the PDF files by `extractor.py`, fails with `TableMissing` without
+/// them
 #[test]
 fn test_out_of_table_preformance() {
     let mut tables = iaaf_2017_tables_outdoor::Iaaf2017Tables::new();
 
-    assert_eq!(tables.score(OutdoorEvent::Track200m, Gender::Male, Preformance::Time(Duration::from_millis(26150))).unwrap(), 444);
+    assert_eq!(tables.score(OutdoorEvent::Track200m, Gender::Male, Preformance::Time(Duration::from_millis(26150))).unwrap().points, 444);
 
     // falls in between two table entries
-    assert_eq!(tables.score(OutdoorEvent::Track100m, Gender::Male, Preformance::Time(Duration::from_millis(15340))).unwrap(), 67);
+    assert_eq!(tables.score(OutdoorEvent::Track100m, Gender::Male, Preformance::Time(Duration::from_millis(15340))).unwrap().points, 67);
 
     // also falls off the table
-    assert_eq!(tables.score(OutdoorEvent::HighJump, Gender::Female, Preformance::Distance(2.20)).unwrap(), 1399);
+    assert_eq!(tables.score(OutdoorEvent::HighJump, Gender::Female, Preformance::Distance(2.20)).unwrap().points, 1399);
 
     // falls off the table
-    assert_eq!(tables.score(OutdoorEvent::Track100m, Gender::Male, Preformance::Time(Duration::from_millis(9400))).unwrap(), 1400);
-    assert_eq!(tables.score(OutdoorEvent::Track100m, Gender::Female, Preformance::Time(Duration::from_millis(21700))).unwrap(), 0);
+    assert_eq!(tables.score(OutdoorEvent::Track100m, Gender::Male, Preformance::Time(Duration::from_millis(9400))).unwrap().points, 1400);
+    assert_eq!(tables.score(OutdoorEvent::Track100m, Gender::Female, Preformance::Time(Duration::from_millis(21700))).unwrap().points, 0);
+}
+
+#[test]
+fn test_event_not_in_edition() {
+    let mut tables = Edition::Iaaf2017.outdoor();
+    assert_eq!(
+        tables.score(OutdoorEvent::RoadMile, Gender::Male, Preformance::Time(Duration::from_secs(240))),
+        Err(IaafScoringError::TableMissing("Table Outdoor 2017 - MALE - Road Mile.csv".to_string()))
+    );
+    assert_eq!(
+        tables.score(OutdoorEvent::Walk35km, Gender::Female, Preformance::Time(Duration::from_secs(10000))),
+        Err(IaafScoringError::TableMissing("Table Outdoor 2017 - FEMALE - 35km W.csv".to_string()))
+    );
+    assert_eq!(
+        tables.score(OutdoorEvent::Decathlon, Gender::Female, Preformance::Points(6000)),
+        Err(IaafScoringError::GenderNotEligible { event: "Decathlon".to_string(), gender: Gender::Female })
+    );
+}
+
+#[test]
+fn test_equivalent_performance() {
+    let mut method = LinearSprints;
+
+    let equivalent = equivalence::equivalent_performance(
+        &mut method,
+        OutdoorEvent::Track100m,
+        Gender::Male,
+        Preformance::Time(Duration::from_millis(10500)),
+        OutdoorEvent::Track200m,
+        Gender::Female,
+    );
+    assert_eq!(equivalent, Ok(Some(Preformance::Time(Duration::from_secs(21)))));
+
+    // falls off the table, so there is no equivalent
+    let equivalent = equivalence::equivalent_performance(
+        &mut method,
+        OutdoorEvent::Track100m,
+        Gender::Male,
+        Preformance::Time(Duration::from_secs(25)),
+        OutdoorEvent::Track200m,
+        Gender::Male,
+    );
+    assert_eq!(equivalent, Ok(None));
+
+    let table = equivalence::equivalence_table(
+        &mut method,
+        OutdoorEvent::Track100m,
+        Gender::Male,
+        Preformance::Time(Duration::from_millis(10500)),
+    ).unwrap();
+    assert_eq!(table.len(), 4);
+    assert!(table.iter().all(|e| e.points == 950));
+    assert_eq!(table[2].event, OutdoorEvent::Track200m);
+    assert_eq!(table[2].performance, Preformance::Time(Duration::from_secs(21)));
+}
+
+/// `LinearSprints`, with a 400m table that can't be read
+struct CorruptQuarterMile;
+
+impl ScoringMethod<OutdoorEvent> for CorruptQuarterMile {
+    fn edition_name(&self) -> &'static str {
+        "Corrupt"
+    }
+
+    fn lookup(&mut self, event: OutdoorEvent, gender: Gender, performance: Preformance) -> Result<LookupResult, IaafScoringError> {
+        LinearSprints.lookup(event, gender, performance)
+    }
+
+    fn performance_for_points(&mut self, event: OutdoorEvent, gender: Gender, points: u64) -> Result<Preformance, IaafScoringError> {
+        match event {
+            OutdoorEvent::Track400m => Err(IaafScoringError::CorruptArchive("400m".to_string())),
+            _ => LinearSprints.performance_for_points(event, gender, points),
+        }
+    }
+}
+
+#[test]
+fn test_equivalence_table_errors() {
+    let table = equivalence::equivalence_table(
+        &mut CorruptQuarterMile,
+        OutdoorEvent::Track100m,
+        Gender::Male,
+        Preformance::Time(Duration::from_millis(10500)),
+    );
+    assert_eq!(table, Err(IaafScoringError::CorruptArchive("400m".to_string())));
+}
+
+#[test]
+fn test_combined_events() {
+    // Ashton Eaton, 2015 world record
+    let score = CombinedEvent::Decathlon.score(&[
+        time(10.23), Preformance::Distance(7.88), Preformance::Distance(14.52), Preformance::Distance(2.01), time(45.00),
+        time(13.69), Preformance::Distance(43.34), Preformance::Distance(5.20), Preformance::Distance(63.63), time(257.52),
+    ]).unwrap();
+    assert_eq!(score.total, 9045);
+    assert_eq!(score.disciplines[0].points, 1040);
+    assert_eq!(score.disciplines[4].cumulative, 4703);
+
+    // Jackie Joyner-Kersee, 1988 world record
+    let score = CombinedEvent::Heptathlon.score(&[
+        time(12.69), Preformance::Distance(1.86), Preformance::Distance(15.80), time(22.56),
+        Preformance::Distance(7.27), Preformance::Distance(45.66), time(128.51),
+    ]).unwrap();
+    assert_eq!(score.total, 7291);
+
+    // Ashton Eaton, 2012 indoor world record
+    let score = CombinedEvent::IndoorHeptathlon.score(&[
+        time(6.79), Preformance::Distance(8.16), Preformance::Distance(14.56), Preformance::Distance(2.03),
+        time(7.68), Preformance::Distance(5.20), time(152.77),
+    ]).unwrap();
+    assert_eq!(score.total, 6645);
+
+    // Nafissatou Thiam, 2023 indoor world record
+    let score = CombinedEvent::IndoorPentathlon.score(&[
+        time(8.23), Preformance::Distance(1.92), Preformance::Distance(15.54), Preformance::Distance(6.59), time(133.60),
+    ]).unwrap();
+    assert_eq!(score.total, 5055);
+
+    // no mark, or too slow to score any points
+    assert_eq!(CombinedEvent::Heptathlon.disciplines()[1].points(Preformance::NoResult(NonPreformance::Nh)), Ok(0));
+    assert_eq!(CombinedEvent::IndoorHeptathlon.disciplines()[0].points(time(12.0)), Ok(0));
+
+    // wrong amount of performances, and wrong kind of performance
+    assert_eq!(
+        CombinedEvent::IndoorPentathlon.score(&[time(8.23)]),
+        Err(IaafScoringError::PreformanceCountMismatch { expected: 5, found: 1 })
+    );
+    assert_eq!(
+        CombinedEvent::Decathlon.disciplines()[1].points(time(7.88)),
+        Err(IaafScoringError::WrongPreformanceKind {
+            event: "LJ".to_string(),
+            expected: MeasurementKind::Distance,
+            preformance: time(7.88),
+        })
+    );
+}
+
+#[test]
+fn test_wrong_preformance_kind() {
+    let mut tables = iaaf_2017_tables_outdoor::Iaaf2017Tables::new();
+
+    assert_eq!(
+        tables.score(OutdoorEvent::Track100m, Gender::Male, Preformance::Distance(10.5)),
+        Err(IaafScoringError::WrongPreformanceKind {
+            event: "100m".to_string(),
+            expected: MeasurementKind::Time,
+            preformance: Preformance::Distance(10.5),
+        })
+    );
+    assert_eq!(
+        tables.score(OutdoorEvent::HighJump, Gender::Female, time(1.80)),
+        Err(IaafScoringError::WrongPreformanceKind {
+            event: "HJ".to_string(),
+            expected: MeasurementKind::Distance,
+            preformance: time(1.80),
+        })
+    );
+}
+
+#[test]
+fn test_score_with_wind() {
+    let mut method = LinearSprints;
+
+    // 10.50 s scores 950 points on the linear scale
+    let score = wind::score_with_wind(&mut method, OutdoorEvent::Track100m, Gender::Male, time(10.5), Some(-1.0)).unwrap();
+    assert_eq!((score.score.points, score.modification, score.points), (950, 6, 956));
+    assert!(score.record_eligible);
+    assert!(!score.wind_capped);
+
+    let score = wind::score_with_wind(&mut method, OutdoorEvent::Track100m, Gender::Male, time(10.5), Some(2.0)).unwrap();
+    assert_eq!(score.points, 938);
+    assert!(score.record_eligible);
+
+    let score = wind::score_with_wind(&mut method, OutdoorEvent::Track100m, Gender::Male, time(10.5), Some(2.1)).unwrap();
+    assert_eq!(score.points, 937);
+    assert!(score.is_wind_assisted());
+    assert!(!score.record_eligible);
+
+    let score = wind::score_with_wind(&mut method, OutdoorEvent::Track100m, Gender::Male, time(10.5), Some(5.2)).unwrap();
+    assert_eq!((score.modification, score.points), (-24, 926));
+    assert!(score.wind_capped);
+
+    let score = wind::score_with_wind(&mut method, OutdoorEvent::Track200m, Gender::Male, time(21.0), None).unwrap();
+    assert_eq!((score.modification, score.points), (0, 950));
+    assert!(!score.record_eligible);
+
+    assert_eq!(
+        wind::score_with_wind(&mut method, OutdoorEvent::Track100m, Gender::Male, time(10.5), Some(f64::NAN)).map(|s| s.points).map_err(|e| e.to_string()),
+        Err("invalid wind reading NaN".to_string())
+    );
+}
+
+#[test]
+fn test_hand_timed_combined_events() {
+    let decathlon_100m = CombinedEvent::Decathlon.disciplines()[0];
+    assert_eq!(
+        decathlon_100m.points(Preformance::HandTime(Duration::from_millis(10600))),
+        decathlon_100m.points(time(10.84))
+    );
+
+    let decathlon_1500m = CombinedEvent::Decathlon.disciplines()[9];
+    assert_eq!(
+        decathlon_1500m.points(Preformance::HandTime(Duration::from_secs(260))),
+        decathlon_1500m.points(time(260.0))
+    );
+
+    let heptathlon_60m = CombinedEvent::IndoorHeptathlon.disciplines()[0];
+    assert_eq!(
+        heptathlon_60m.points(Preformance::HandTime(Duration::from_millis(6800))),
+        heptathlon_60m.points(time(7.04))
+    );
+}
+
+#[test]
+fn test_performance_score() {
+    let mut method = LinearSprints;
+    let result = method.score(OutdoorEvent::Track200m, Gender::Female, time(22.0)).unwrap();
+
+    let placing = Placing { category: CompetitionCategory::GL, round: Round::Final, place: 2 };
+    let score = PerformanceScore::new(result, placing);
+    assert_eq!((score.result.points, score.placing_score, score.total), (900, 145, 1045));
+}
+
+#[test]
+fn test_ranking_score() {
+    let mut method = LinearSprints;
+    let result = |date, competition: &str, event, seconds, place| DatedResult {
+        date,
+        competition: competition.to_string(),
+        event,
+        gender: Gender::Male,
+        performance: time(seconds),
+        wind: None,
+        placing: Placing { category: CompetitionCategory::F, round: Round::Final, place },
+    };
+
+    let results = vec![
+        // 950 + 15 points
+        result(Date::new(2023, 5, 1), "A", OutdoorEvent::Track100m, 10.5, 1),
+        // the heat of the same competition doesn't count as well, 940 points
+        result(Date::new(2023, 5, 1), "A", OutdoorEvent::Track100m, 10.6, 20),
+        // 930 + 10 points
+        result(Date::new(2023, 6, 1), "B", OutdoorEvent::Track100m, 10.7, 2),
+        // outside of the window
+        result(Date::new(2022, 4, 1), "C", OutdoorEvent::Track100m, 10.0, 1),
+        // only one 200m counts, 1000 and 950 points
+        result(Date::new(2023, 7, 1), "D", OutdoorEvent::Track200m, 20.0, 4),
+        result(Date::new(2023, 8, 1), "E", OutdoorEvent::Track200m, 21.0, 4),
+    ];
+
+    let rules = RankingRules::for_event(OutdoorEvent::Track100m)
+        .with_other_event(OutdoorEvent::Track200m)
+        .with_max_other_events(1);
+    let ranking = rankings::ranking_score(&mut method, &results, &rules, Date::new(2023, 9, 1)).unwrap();
+
+    let counted: Vec<u64> = ranking.counted.iter().map(|c| c.score.total).collect();
+    assert_eq!(counted, vec![1000, 965, 940]);
+    assert!((ranking.score - 2905.0 / 3.0).abs() < 1e-9);
+}
+
+#[cfg(feature = "runtime-tables")]
+#[test]
+fn test_load_csv_directory() {
+    let dir = TableDir::new("tables");
+    dir.write("Table Outdoor 2017 - FEMALE - 3000m SC.csv", "performance,points\n600.0,1200\n601.0,1198\n602.0,1196\n")
+        .write("notes.txt", "not a table");
+
+    assert_eq!(dir.load(), Ok(1));
+    let score = Edition::Iaaf2017
+        .outdoor()
+        .score(OutdoorEvent::Track3kmSteeplechase, Gender::Female, time(601.0))
+        .unwrap();
+    assert_eq!(score.points, 1198);
+    assert!(EmbeddedTables::load_times().iter().any(|load| load.table == "Table Outdoor 2017 - FEMALE - 3000m SC.csv"));
+}
+
+#[cfg(feature = "runtime-tables")]
+#[test]
+fn test_score_batch() {
+    let dir = TableDir::new("batch");
+    dir.write("Table Outdoor 2017 - MALE - 3000m SC.csv", "performance,points\n480.0,1250\n481.0,1248\n482.0,1246\n483.0,1244\n");
+    assert_eq!(dir.load(), Ok(1));
+
+    let results = [
+        (OutdoorEvent::Track3kmSteeplechase, Gender::Male, time(481.0)),
+        (OutdoorEvent::Track3kmSteeplechase, Gender::Male, time(482.5)),
+        (OutdoorEvent::Track3kmSteeplechase, Gender::Male, Preformance::Distance(8.0)),
+        (OutdoorEvent::Track3kmSteeplechase, Gender::Male, time(479.0)),
+        (OutdoorEvent::Track3kmSteeplechase, Gender::Male, time(490.0)),
+    ];
+    let mut method = Edition::Iaaf2017.outdoor();
+    let scores = method.score_batch(&results);
+
+    assert_eq!(scores.len(), results.len());
+    for (score, &(event, gender, performance)) in scores.into_iter().zip(results.iter()) {
+        assert_eq!(score, method.score(event, gender, performance));
+    }
+    assert_eq!(method.score_batch(&results)[1].as_ref().unwrap().points, 1244);
+    assert!(matches!(
+        method.score_batch(&results)[2],
+        Err(IaafScoringError::WrongPreformanceKind { .. })
+    ));
+
+    // an event the gender doesn't contest fails the same way, whatever the performance
+    let ineligible = [(OutdoorEvent::Decathlon, Gender::Female, Preformance::Distance(1.0))];
+    let expected = Err(IaafScoringError::GenderNotEligible { event: "Decathlon".to_string(), gender: Gender::Female });
+    assert_eq!(method.score(OutdoorEvent::Decathlon, Gender::Female, Preformance::Distance(1.0)), expected);
+    assert_eq!(method.score_batch(&ineligible), vec![expected]);
+}
+
+#[cfg(feature = "runtime-tables")]
+#[test]
+fn test_fit_table_too_few_rows() {
+    let dir = TableDir::new("fit");
+    dir.write("Table Outdoor 2022 - MALE - 2000m SC.csv", "performance,points\n330.0,1250\n331.0,1248\n");
+    assert_eq!(dir.load(), Ok(1));
+
+    assert_eq!(
+        formula::fit_table(Edition::Wa2022, OutdoorEvent::Track2kmSteeplechase, Gender::Male),
+        Err(IaafScoringError::CurveFitFailed("Table Outdoor 2022 - MALE - 2000m SC.csv".to_string()))
+    );
 }
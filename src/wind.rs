@@ -0,0 +1,118 @@
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use crate::error::IaafScoringError;
+use crate::event::OutdoorEvent;
+use crate::gender::Gender;
+use crate::methods::{Score, ScoringMethod};
+use crate::preformance::Preformance;
+
+/// The strongest tailwind (m/s) with which a mark still counts for records
+pub const RECORD_WIND_LIMIT: f64 = 2.0;
+
+/// Winds beyond this strength (m/s) either way aren't modified any further: the wind modification
+/// table of the World Athletics Rankings rules ends at -4.0 and +4.0 m/s
+pub const MAX_MODIFIED_WIND: f64 = 4.0;
+
+/// A score with the wind modification of the World Athletics rankings applied
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WindAdjustedScore {
+    /// The score of the performance itself, without any wind modification
+    pub score: Score<OutdoorEvent>,
+    /// The wind reading in m/s, positive for a tailwind
+    pub wind: Option<f64>,
+    /// The points added to (headwind) or subtracted from (tailwind) the score
+    pub modification: i64,
+    /// Whether the wind was stronger than `MAX_MODIFIED_WIND`, so the modification is the one for
+    /// a wind of 4.0 m/s and not for the wind that was measured
+    pub wind_capped: bool,
+    /// The score with the wind modification applied, never below 0
+    pub points: u64,
+    /// Whether the mark counts for records: the wind has to be measured and at most +2.0 m/s in
+    /// events where the wind is measured
+    pub record_eligible: bool,
+}
+
+impl WindAdjustedScore {
+    /// Whether the mark was set with a tailwind over +2.0 m/s
+    pub fn is_wind_assisted(&self) -> bool {
+        self.wind.is_some_and(is_wind_assisted)
+    }
+}
+
+fn is_wind_assisted(wind: f64) -> bool {
+    // readings are given to a tenth of a m/s, leave some room for floating point errors
+    wind > RECORD_WIND_LIMIT + 1e-9
+}
+
+/// Returns the wind modification in points for the event: points are added for a headwind and
+/// subtracted for a tailwind, at the rate of `OutdoorEvent::wind_modification_rate`, up to a wind
+/// of `MAX_MODIFIED_WIND` either way (see `is_wind_capped`). Events where the wind isn't measured
+/// are never modified
+pub fn wind_modification(event: OutdoorEvent, wind: f64) -> i64 {
+    match event.wind_modification_rate() {
+        Some(rate) => (-wind.clamp(-MAX_MODIFIED_WIND, MAX_MODIFIED_WIND) * rate).round() as i64,
+        None => 0,
+    }
+}
+
+/// Whether the wind is stronger than the modification covers in the event, see
+/// `wind_modification`
+pub fn is_wind_capped(event: OutdoorEvent, wind: f64) -> bool {
+    // with the same tolerance as `is_wind_assisted`
+    event.wind_modification_rate().is_some() && wind.abs() > MAX_MODIFIED_WIND + 1e-9
+}
+
+/// Scores the performance and applies the wind modification for the wind reading (m/s, positive
+/// for a tailwind), see `wind_modification`. Without a wind reading the score isn't modified
+pub fn score_with_wind<M: ScoringMethod<OutdoorEvent> + ?Sized>(
+    method: &mut M,
+    event: OutdoorEvent,
+    gender: Gender,
+    performance: Preformance,
+    wind: Option<f64>,
+) -> Result<WindAdjustedScore, IaafScoringError> {
+    if let Some(wind) = wind.filter(|wind| !wind.is_finite()) {
+        return Err(IaafScoringError::InvalidWind(wind));
+    }
+
+    let score = method.score(event, gender, performance)?;
+    let (modification, wind_capped) = match (wind, performance) {
+        // there is nothing to modify if the athlete didn't produce a preformance
+        (_, Preformance::NoResult(_)) | (None, _) => (0, false),
+        (Some(wind), _) => (wind_modification(event, wind), is_wind_capped(event, wind)),
+    };
+
+    let record_eligible = match (event.wind_modification_rate(), wind) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(_), Some(wind)) => !is_wind_assisted(wind),
+    };
+
+    Ok(WindAdjustedScore {
+        score,
+        wind,
+        modification,
+        wind_capped,
+        points: score.points.saturating_add_signed(modification),
+        record_eligible,
+    })
+}
+
+#[test]
+fn test_wind_modification() {
+    assert_eq!(wind_modification(OutdoorEvent::Track100m, 2.0), -12);
+    assert_eq!(wind_modification(OutdoorEvent::Track100m, -1.5), 9);
+    assert_eq!(wind_modification(OutdoorEvent::Track100m, 0.0), 0);
+    assert_eq!(wind_modification(OutdoorEvent::Track100m, 6.3), -24);
+    assert_eq!(wind_modification(OutdoorEvent::Track200m, 2.0), -6);
+    assert_eq!(wind_modification(OutdoorEvent::Track200m, -0.3), 1);
+    assert_eq!(wind_modification(OutdoorEvent::TripleJump, 1.1), -7);
+    assert_eq!(wind_modification(OutdoorEvent::Track400m, 3.0), 0);
+    assert_eq!(wind_modification(OutdoorEvent::HighJump, 3.0), 0);
+
+    assert_eq!(wind_modification(OutdoorEvent::Track100m, -5.0), 24);
+    assert!(is_wind_capped(OutdoorEvent::Track100m, 6.3));
+    assert!(is_wind_capped(OutdoorEvent::LongJump, -4.1));
+    assert!(!is_wind_capped(OutdoorEvent::Track100m, 4.0));
+    assert!(!is_wind_capped(OutdoorEvent::Track400m, 6.3));
+}
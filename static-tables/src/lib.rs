@@ -0,0 +1,98 @@
+//! Compiles the scoring tables into `iaaf_scoring` for its `static-tables` feature. A crate of its
+//! own, so `csv`, `lzma-rs` and `tar` are only build dependencies when the feature is enabled, and
+//! don't clash with the same dependencies of the `runtime-tables` feature
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// Decompresses the archives in `resources` and writes every table to the file at `path` as a
+/// sorted array, so the crate doesn't have to decompress and parse them at runtime
+pub fn write_static_tables(resources: &Path, path: &Path) {
+    let mut tables = Vec::new();
+    let mut archives = fs::read_dir(resources)
+        .map(|dir| dir.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    archives.sort();
+
+    for archive in archives {
+        let name = archive.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if !name.ends_with(".tar.lzma") || name.starts_with("wma") {
+            continue;
+        }
+
+        for (path, data) in unpack(&archive) {
+            if path.ends_with(".csv") {
+                tables.push((path.clone(), parse(&path, data)));
+            }
+        }
+    }
+
+    // the tables are looked up with a binary search on their name
+    tables.sort_by(|a, b| a.0.cmp(&b.0));
+    tables.dedup_by(|a, b| a.0 == b.0);
+
+    let mut out = String::new();
+    out.push_str("/// Every table, sorted by name, with the rows sorted best performance first\n");
+    out.push_str("pub(super) static TABLES: &[(&str, &[(u32, u16)])] = &[\n");
+    for (name, rows) in &tables {
+        write!(out, "    ({:?}, &[", name).unwrap();
+        for (performance, points) in rows {
+            write!(out, "({}, {}),", performance, points).unwrap();
+        }
+        out.push_str("]),\n");
+    }
+    out.push_str("];\n");
+
+    fs::write(path, out).expect("can't write static_tables.rs");
+}
+
+fn unpack(path: &Path) -> Vec<(String, Vec<u8>)> {
+    let data = fs::read(path).unwrap_or_else(|e| panic!("can't read {}: {}", path.display(), e));
+    let mut buf = Vec::new();
+    lzma_rs::lzma_decompress(&mut Cursor::new(data), &mut buf)
+        .unwrap_or_else(|e| panic!("can't decompress {}: {}", path.display(), e));
+
+    let mut files = Vec::new();
+    let mut archive = tar::Archive::new(Cursor::new(buf));
+    for entry in archive.entries().expect("corrupt archive") {
+        let mut file = entry.expect("corrupt archive");
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).expect("corrupt archive");
+
+        let path = file.path().expect("corrupt archive");
+        if let Some(name) = path.file_name() {
+            files.push((name.to_string_lossy().to_string(), buf));
+        }
+    }
+
+    files
+}
+
+/// Parses a table to rows of the performance in thousandths (of seconds, meters or points) and
+/// the points, best performance first
+fn parse(name: &str, data: Vec<u8>) -> Vec<(u32, u16)> {
+    let mut rows = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(Cursor::new(data))
+        .records()
+        .map(|record| {
+            let record = record.unwrap_or_else(|e| panic!("malformed row in {}: {}", name, e));
+            let performance: f64 = record[0].parse().unwrap_or_else(|e| panic!("{}: {}", name, e));
+            let points: u16 = record[1].parse().unwrap_or_else(|e| panic!("{}: {}", name, e));
+
+            ((performance * 1000.0).round() as u32, points)
+        })
+        .collect::<Vec<_>>();
+
+    // the best performance has the most points, the rest of the table decides which way that is
+    let best = rows.iter().max_by_key(|row| row.1).map(|row| row.0);
+    let worst = rows.iter().min_by_key(|row| row.1).map(|row| row.0);
+    if best < worst {
+        rows.sort_by_key(|row| row.0);
+    } else {
+        rows.sort_by_key(|row| std::cmp::Reverse(row.0));
+    }
+
+    rows
+}